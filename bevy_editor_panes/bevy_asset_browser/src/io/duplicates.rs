@@ -0,0 +1,328 @@
+//! Content hashing used to find byte-identical files inside an asset source
+
+use std::{
+    collections::HashMap,
+    fs::File,
+    hash::{DefaultHasher, Hasher},
+    io::{Read, Seek, SeekFrom},
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use crate::DuplicateGroup;
+
+/// Size of the buffer used to stream file content into the hasher
+const HASH_BUFFER_SIZE: usize = 8 * 1024;
+
+/// Size of the chunks read at the start and the end of a file by [`partial_hash`]
+const PARTIAL_HASH_CHUNK_SIZE: u64 = HASH_BUFFER_SIZE as u64;
+
+/// Progress of a running duplicate scan, shared between the scan task and the UI
+#[derive(Default, Debug)]
+pub struct ScanProgress {
+    /// Number of files hashed so far
+    pub scanned: AtomicUsize,
+    /// Number of files to hash, known once the directory tree has been walked
+    pub total: AtomicUsize,
+    /// Number of files which content had to be hashed entirely to be told apart
+    pub fully_hashed: AtomicUsize,
+}
+
+/// Hash the whole content of a file
+pub fn content_hash(path: &Path) -> std::io::Result<u64> {
+    let mut file = File::open(path)?;
+    let mut hasher = DefaultHasher::new();
+    let mut buffer = [0; HASH_BUFFER_SIZE];
+    loop {
+        let read = file.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.write(&buffer[..read]);
+    }
+    Ok(hasher.finish())
+}
+
+/// Hash the first and last chunks of a file of `size` bytes, which tells apart most files of the same size
+///
+/// Files no bigger than two chunks are hashed entirely, see [`partial_hash_is_complete`].
+pub fn partial_hash(path: &Path, size: u64) -> std::io::Result<u64> {
+    if partial_hash_is_complete(size) {
+        return content_hash(path);
+    }
+    let mut file = File::open(path)?;
+    let mut hasher = DefaultHasher::new();
+    let mut buffer = [0; HASH_BUFFER_SIZE];
+    file.read_exact(&mut buffer)?;
+    hasher.write(&buffer);
+    file.seek(SeekFrom::End(-(PARTIAL_HASH_CHUNK_SIZE as i64)))?;
+    file.read_exact(&mut buffer)?;
+    hasher.write(&buffer);
+    Ok(hasher.finish())
+}
+
+/// Fill `buffer` from `file`, only stopping early at the end of the file, and return the number of bytes read
+fn read_chunk(file: &mut File, buffer: &mut [u8]) -> std::io::Result<usize> {
+    let mut filled = 0;
+    while filled < buffer.len() {
+        match file.read(&mut buffer[filled..])? {
+            0 => break,
+            read => filled += read,
+        }
+    }
+    Ok(filled)
+}
+
+/// Compare the content of two files byte for byte
+pub fn same_content(left: &Path, right: &Path) -> std::io::Result<bool> {
+    if std::fs::metadata(left)?.len() != std::fs::metadata(right)?.len() {
+        return Ok(false);
+    }
+    let (mut left, mut right) = (File::open(left)?, File::open(right)?);
+    let mut left_buffer = [0; HASH_BUFFER_SIZE];
+    let mut right_buffer = [0; HASH_BUFFER_SIZE];
+    loop {
+        let read = read_chunk(&mut left, &mut left_buffer)?;
+        if read != read_chunk(&mut right, &mut right_buffer)?
+            || left_buffer[..read] != right_buffer[..read]
+        {
+            return Ok(false);
+        }
+        if read == 0 {
+            return Ok(true);
+        }
+    }
+}
+
+/// Split files sharing a hash into the groups of files with the same bytes, in case of a hash collision
+fn split_identical(paths: Vec<PathBuf>) -> Vec<Vec<PathBuf>> {
+    let mut groups: Vec<Vec<PathBuf>> = Vec::new();
+    for path in paths {
+        let group = groups.iter_mut().find(|group| {
+            same_content(&group[0], &path).unwrap_or_else(|e| {
+                eprintln!("Failed to compare {}: {e}", path.display());
+                false
+            })
+        });
+        match group {
+            Some(group) => group.push(path),
+            None => groups.push(vec![path]),
+        }
+    }
+    groups
+}
+
+/// Check if [`partial_hash`] covers the whole content of a file of `size` bytes
+pub fn partial_hash_is_complete(size: u64) -> bool {
+    size <= 2 * PARTIAL_HASH_CHUNK_SIZE
+}
+
+/// Group `paths` by their `hash`, files that can't be read are skipped and counted as scanned
+fn group_by_hash(
+    paths: Vec<PathBuf>,
+    hash: impl Fn(&Path) -> std::io::Result<u64>,
+    progress: &ScanProgress,
+) -> HashMap<u64, Vec<PathBuf>> {
+    let mut groups: HashMap<u64, Vec<PathBuf>> = HashMap::new();
+    for path in paths {
+        match hash(&path) {
+            Ok(hash) => groups.entry(hash).or_default().push(path),
+            Err(e) => {
+                eprintln!("Failed to hash {}: {e}", path.display());
+                progress.scanned.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+    }
+    groups
+}
+
+/// Recursively collect every file under `directory`
+fn collect_files(directory: &Path, files: &mut Vec<PathBuf>) -> std::io::Result<()> {
+    for entry in std::fs::read_dir(directory)? {
+        let entry = entry?;
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            collect_files(&entry.path(), files)?;
+        } else if file_type.is_file() {
+            files.push(entry.path());
+        }
+    }
+    Ok(())
+}
+
+/// Find all the groups of byte-identical files under `root`
+///
+/// Files are first grouped by size, then by a [`partial_hash`] of their first and last chunks,
+/// and only the files still colliding after that are hashed entirely.
+/// Files sharing a hash are finally compared byte for byte, so a hash collision can't group different files.
+/// Returned paths are relative to `root`, groups are sorted by path so the result is stable between scans.
+/// Files that can't be read are skipped.
+pub fn find_duplicates(
+    root: &Path,
+    progress: &ScanProgress,
+) -> std::io::Result<Vec<DuplicateGroup>> {
+    let mut files = Vec::new();
+    collect_files(root, &mut files)?;
+    progress.total.store(files.len(), Ordering::Relaxed);
+
+    let mut files_by_size: HashMap<u64, Vec<PathBuf>> = HashMap::new();
+    for path in files {
+        match std::fs::metadata(&path) {
+            Ok(metadata) => files_by_size.entry(metadata.len()).or_default().push(path),
+            Err(e) => {
+                eprintln!("Failed to read metadata of {}: {e}", path.display());
+                progress.scanned.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+    }
+
+    let mut groups = Vec::new();
+    let mut add_group = |size: u64, paths: Vec<PathBuf>| {
+        progress.scanned.fetch_add(paths.len(), Ordering::Relaxed);
+        if paths.len() < 2 {
+            return;
+        }
+        for identical in split_identical(paths) {
+            if identical.len() < 2 {
+                continue;
+            }
+            let mut paths: Vec<PathBuf> = identical
+                .iter()
+                .map(|path| path.strip_prefix(root).unwrap_or(path).to_path_buf())
+                .collect();
+            paths.sort();
+            groups.push(DuplicateGroup { size, paths });
+        }
+    };
+    for (size, paths) in files_by_size {
+        if paths.len() < 2 {
+            add_group(size, paths);
+            continue;
+        }
+        for same_ends in
+            group_by_hash(paths, |path| partial_hash(path, size), progress).into_values()
+        {
+            if same_ends.len() < 2 || partial_hash_is_complete(size) {
+                add_group(size, same_ends);
+                continue;
+            }
+            progress
+                .fully_hashed
+                .fetch_add(same_ends.len(), Ordering::Relaxed);
+            for same_content in group_by_hash(same_ends, content_hash, progress).into_values() {
+                add_group(size, same_content);
+            }
+        }
+    }
+    groups.sort_by(|left, right| left.paths.cmp(&right.paths));
+    Ok(groups)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::TempDir;
+
+    #[test]
+    fn find_duplicate_groups() {
+        let root = TempDir::new("duplicates");
+        std::fs::create_dir_all(root.join("textures/old")).unwrap();
+        std::fs::write(root.join("player.png"), b"player").unwrap();
+        std::fs::write(root.join("textures/player.png"), b"player").unwrap();
+        std::fs::write(root.join("textures/old/player_copy.png"), b"player").unwrap();
+        std::fs::write(root.join("enemy.png"), b"enemy").unwrap();
+        std::fs::write(root.join("textures/enemy.png"), b"enemy!").unwrap();
+        std::fs::write(root.join("a.txt"), b"same").unwrap();
+        std::fs::write(root.join("b.txt"), b"same").unwrap();
+
+        let progress = ScanProgress::default();
+        let groups = find_duplicates(root.path(), &progress).unwrap();
+
+        assert_eq!(
+            groups,
+            vec![
+                DuplicateGroup {
+                    size: 4,
+                    paths: vec![PathBuf::from("a.txt"), PathBuf::from("b.txt")],
+                },
+                DuplicateGroup {
+                    size: 6,
+                    paths: vec![
+                        PathBuf::from("player.png"),
+                        PathBuf::from("textures/old/player_copy.png"),
+                        PathBuf::from("textures/player.png"),
+                    ],
+                },
+            ]
+        );
+        assert_eq!(progress.total.load(Ordering::Relaxed), 7);
+        assert_eq!(progress.scanned.load(Ordering::Relaxed), 7);
+        assert_eq!(progress.fully_hashed.load(Ordering::Relaxed), 0);
+    }
+
+    #[test]
+    fn same_content_compares_every_byte() {
+        let root = TempDir::new("same_content");
+        let content = vec![7; 3 * HASH_BUFFER_SIZE + 5];
+        let mut last_byte_differs = content.clone();
+        *last_byte_differs.last_mut().unwrap() = 0;
+        std::fs::write(root.join("a.bin"), &content).unwrap();
+        std::fs::write(root.join("b.bin"), &content).unwrap();
+        std::fs::write(root.join("c.bin"), &last_byte_differs).unwrap();
+        std::fs::write(root.join("short.bin"), &content[1..]).unwrap();
+
+        assert!(same_content(&root.join("a.bin"), &root.join("b.bin")).unwrap());
+        assert!(!same_content(&root.join("a.bin"), &root.join("c.bin")).unwrap());
+        assert!(!same_content(&root.join("a.bin"), &root.join("short.bin")).unwrap());
+        assert!(same_content(&root.join("a.bin"), &root.join("missing.bin")).is_err());
+        assert_eq!(
+            split_identical(
+                ["a.bin", "c.bin", "b.bin"]
+                    .map(|name| root.join(name))
+                    .to_vec()
+            ),
+            [
+                vec![root.join("a.bin"), root.join("b.bin")],
+                vec![root.join("c.bin")]
+            ]
+        );
+    }
+
+    #[test]
+    fn partial_hash_avoids_full_reads() {
+        let root = TempDir::new("partial_hash");
+        let content = vec![7; 4 * HASH_BUFFER_SIZE];
+        let with_byte_at = |index: usize| {
+            let mut content = content.clone();
+            content[index] = 0;
+            content
+        };
+        // Same first and last chunks, only a full hash tells them apart
+        std::fs::write(root.join("middle_a.bin"), &content).unwrap();
+        std::fs::write(
+            root.join("middle_b.bin"),
+            with_byte_at(2 * HASH_BUFFER_SIZE),
+        )
+        .unwrap();
+        // Same prefix, told apart by the last chunk
+        std::fs::write(
+            root.join("end_a.bin"),
+            with_byte_at(4 * HASH_BUFFER_SIZE - 1),
+        )
+        .unwrap();
+        std::fs::write(
+            root.join("end_b.bin"),
+            with_byte_at(4 * HASH_BUFFER_SIZE - 2),
+        )
+        .unwrap();
+        // Unique size, never hashed
+        std::fs::write(root.join("unique.bin"), vec![7; HASH_BUFFER_SIZE]).unwrap();
+
+        let progress = ScanProgress::default();
+        let groups = find_duplicates(root.path(), &progress).unwrap();
+
+        assert!(groups.is_empty());
+        assert_eq!(progress.scanned.load(Ordering::Relaxed), 5);
+        assert_eq!(progress.fully_hashed.load(Ordering::Relaxed), 2);
+    }
+}
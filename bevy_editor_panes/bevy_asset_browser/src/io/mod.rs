@@ -1,30 +1,27 @@
 //! this module encapsulate all the asset browser IO operations
 
+pub(crate) mod duplicates;
 pub(crate) mod task;
+pub(crate) mod watcher;
 
-use std::{fs::create_dir_all, path::PathBuf};
+use std::{fs::create_dir, path::PathBuf};
 
 /// Create a new folder called "New Folder" in the parent directory
-/// If a folder with the same name already exists, it will increment the name until it's unique
-pub fn create_new_folder(mut parent: PathBuf) -> std::io::Result<String> {
-    parent.push("New Folder");
-    // increment name until it's unique
-    let mut index = 0;
-    while parent.exists() {
-        // increment name and rename last part of the path
-        index += 1;
-        parent.pop();
-        parent.push(format!("New Folder {index}"));
+/// If a folder with the same name already exists, it will increment the name until it's unique (e.g. "New Folder 2")
+pub fn create_new_folder(parent: PathBuf) -> std::io::Result<String> {
+    // increment name until it's unique: "New Folder", "New Folder 2", "New Folder 3"...
+    let mut folder_name = "New Folder".to_string();
+    let mut index = 1;
+    loop {
+        match create_dir(parent.join(&folder_name)) {
+            Ok(()) => return Ok(folder_name),
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {
+                index += 1;
+                folder_name = format!("New Folder {index}");
+            }
+            Err(e) => return Err(e),
+        }
     }
-    create_dir_all(&parent)?;
-    Ok(parent
-        .components()
-        .next_back()
-        .unwrap()
-        .as_os_str()
-        .to_str()
-        .unwrap()
-        .to_string())
 }
 
 /// Create a new rust file with an empty system inside
@@ -67,16 +64,157 @@ pub fn open_in_file_manager(path: PathBuf) -> std::io::Result<()> {
     std::process::Command::new("open")
         .arg(path.as_os_str().to_str().unwrap())
         .spawn()?;
+    #[cfg(not(any(target_os = "windows", target_os = "linux", target_os = "macos")))]
+    return Err(std::io::Error::new(
+        std::io::ErrorKind::Unsupported,
+        format!("No file manager known to open {}", path.display()),
+    ));
+    #[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
     Ok(())
 }
 
-/// Delete a file
+/// Reveal a file in the file manager of the target os, or open a folder in it
+///
+/// The file is only selected with the `opener` feature, otherwise its parent folder is opened.
+pub fn reveal_in_file_manager(path: PathBuf) -> std::io::Result<()> {
+    if !path.exists() {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            format!("{} doesn't exist", path.display()),
+        ));
+    }
+    #[cfg(feature = "opener")]
+    return if path.is_dir() {
+        opener::open(&path)
+    } else {
+        opener::reveal(&path)
+    }
+    .map_err(std::io::Error::other);
+    #[cfg(not(feature = "opener"))]
+    match path.parent() {
+        Some(parent) if !path.is_dir() => open_in_file_manager(parent.to_path_buf()),
+        _ => open_in_file_manager(path),
+    }
+}
+
+/// Delete a file, or move it to the OS trash with the `trash` feature
 pub fn delete_file(path: PathBuf) -> std::io::Result<()> {
-    std::fs::remove_file(path)?;
-    Ok(())
+    #[cfg(feature = "trash")]
+    return move_to_trash(path);
+    #[cfg(not(feature = "trash"))]
+    std::fs::remove_file(path)
 }
-/// Delete a folder and all its content
+
+/// Delete a folder and all its content, or move it to the OS trash with the `trash` feature
 pub fn delete_folder(path: PathBuf) -> std::io::Result<()> {
-    std::fs::remove_dir_all(path)?;
-    Ok(())
+    #[cfg(feature = "trash")]
+    return move_to_trash(path);
+    #[cfg(not(feature = "trash"))]
+    std::fs::remove_dir_all(path)
+}
+
+/// Move a file or folder to the OS trash, so it can be restored
+#[cfg(feature = "trash")]
+fn move_to_trash(path: PathBuf) -> std::io::Result<()> {
+    if !path.exists() {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            format!("{} doesn't exist", path.display()),
+        ));
+    }
+    trash::delete(&path).map_err(std::io::Error::other)
+}
+
+/// Characters that aren't allowed in file names on at least one of the supported platforms
+const INVALID_NAME_CHARS: [char; 9] = ['/', '\\', ':', '*', '?', '"', '<', '>', '|'];
+
+/// Rename the file or folder at `path` to `new_name`, keeping it in the same directory
+///
+/// Fails if the new name is invalid or already taken, so an existing entry is never overwritten.
+pub fn rename_entry(path: PathBuf, new_name: &str) -> std::io::Result<PathBuf> {
+    let new_name = new_name.trim();
+    if new_name.is_empty()
+        || new_name == "."
+        || new_name == ".."
+        || new_name.contains(INVALID_NAME_CHARS)
+    {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!("Invalid name: {new_name:?}"),
+        ));
+    }
+    let new_path = path.with_file_name(new_name);
+    if new_path == path {
+        return Ok(new_path);
+    }
+    // Only changing the case points to the same entry on case-insensitive filesystems
+    let same_entry = || std::fs::canonicalize(&path).ok() == std::fs::canonicalize(&new_path).ok();
+    if new_path.exists() && !same_entry() {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::AlreadyExists,
+            format!("{new_name} already exists"),
+        ));
+    }
+    std::fs::rename(&path, &new_path)?;
+    Ok(new_path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::TempDir;
+
+    #[test]
+    fn new_folder_names_are_unique() {
+        let root = TempDir::new("new_folder");
+
+        let names: Vec<String> = (0..3)
+            .map(|_| create_new_folder(root.path().to_path_buf()).unwrap())
+            .collect();
+
+        assert_eq!(names, ["New Folder", "New Folder 2", "New Folder 3"]);
+        assert!(create_new_folder(root.join("missing")).is_err());
+    }
+
+    #[test]
+    fn reveal_missing_entry_fails() {
+        let root = TempDir::new("reveal_missing");
+        let missing = root.join("missing.png");
+        assert_eq!(
+            reveal_in_file_manager(missing).unwrap_err().kind(),
+            std::io::ErrorKind::NotFound
+        );
+    }
+
+    #[test]
+    fn rename_entry_keeps_existing_entries() {
+        let root = TempDir::new("rename");
+        std::fs::create_dir_all(root.join("textures")).unwrap();
+        std::fs::write(root.join("player.png"), b"player").unwrap();
+        std::fs::write(root.join("enemy.png"), b"enemy").unwrap();
+
+        let renamed = rename_entry(root.join("player.png"), "hero.png");
+        let folder_renamed = rename_entry(root.join("textures"), "sprites");
+        let collision = rename_entry(root.join("hero.png"), "enemy.png");
+        let invalid = rename_entry(root.join("hero.png"), "hero/../player.png");
+        let empty = rename_entry(root.join("hero.png"), "  ");
+        let hero_content = std::fs::read(root.join("hero.png")).unwrap();
+        let enemy_content = std::fs::read(root.join("enemy.png")).unwrap();
+        let sprites_exists = root.join("sprites").is_dir();
+
+        assert_eq!(renamed.unwrap(), root.join("hero.png"));
+        assert_eq!(folder_renamed.unwrap(), root.join("sprites"));
+        assert!(sprites_exists);
+        assert_eq!(
+            collision.unwrap_err().kind(),
+            std::io::ErrorKind::AlreadyExists
+        );
+        assert_eq!(
+            invalid.unwrap_err().kind(),
+            std::io::ErrorKind::InvalidInput
+        );
+        assert_eq!(empty.unwrap_err().kind(), std::io::ErrorKind::InvalidInput);
+        assert_eq!(hero_content, b"player");
+        assert_eq!(enemy_content, b"enemy");
+    }
 }
@@ -1,13 +1,43 @@
-use crate::{AssetBrowserLocation, DirectoryContent, DirectoryContentOrder, Entry};
+use std::sync::{
+    Arc, Mutex,
+    mpsc::{Receiver, channel},
+};
+
+use crate::{
+    AssetBrowserLocation, CaseCollisions, CustomDirectoryContentOrder, DefaultSourceFilePath,
+    DirectoryAccess, DirectoryContent, DirectoryContentOrder, DirectoryEntryDetails,
+    DirectoryFetchError, DuplicateGroup, DuplicateGroups, Entry, EntryDetails, IgnorePatterns,
+    ShowHiddenFiles,
+};
 use bevy::{
-    asset::io::AssetSourceBuilders,
+    asset::io::{AssetReaderError, AssetSourceBuilders, AssetSourceId},
     prelude::*,
     tasks::{IoTaskPool, Task, block_on, futures_lite::StreamExt, poll_once},
 };
 
+use super::duplicates::{ScanProgress, find_duplicates};
+
 #[derive(Component)]
 /// The task that fetches the content of current [`AssetBrowserLocation`]
-pub(crate) struct FetchDirectoryContentTask(Task<DirectoryContent>);
+///
+/// The entries are streamed to [`poll_task`] as they are read, so the first ones are displayed
+/// before the whole directory is scanned.
+pub(crate) struct FetchDirectoryContentTask {
+    /// The location the task was spawned for, its result is stale once the location changed
+    location: AssetBrowserLocation,
+    /// The entries read since the task was last polled, with their details
+    entries: Mutex<Receiver<(Entry, EntryDetails)>>,
+    /// Whether the [`DirectoryContent`] already holds entries of this task instead of the previous content
+    streamed: bool,
+    task: Task<(DirectoryAccess, Option<DirectoryFetchError>)>,
+}
+
+/// Changed by [`poll_task`] when entries are streamed into a [`DirectoryContent`] already displayed
+///
+/// The content is then modified without triggering its change detection, so the UI only adds the new tiles
+/// instead of being rebuilt, see [`refresh_streamed_tiles`](crate::ui::directory_content::refresh_streamed_tiles).
+#[derive(Resource, Default)]
+pub(crate) struct StreamedEntries;
 
 pub(crate) fn fetch_task_is_running(
     task_query: Query<(Entity, &FetchDirectoryContentTask)>,
@@ -15,30 +45,136 @@ pub(crate) fn fetch_task_is_running(
     task_query.iter().next().is_some()
 }
 
-/// Poll the [`FetchDirectoryContentTask`] to check if it's done
-/// If it's done, despawn the task entity and insert the result into [`DirectoryContent`]
+/// Poll the [`FetchDirectoryContentTask`] to add the entries read so far to the [`DirectoryContent`], keeping it sorted,
+/// and their details to the [`DirectoryEntryDetails`]
+/// Only the first entries replace the previous content as a change, the next ones are signaled by [`StreamedEntries`].
+/// Once it's done, despawn the task entity and insert the result into [`DirectoryAccess`], [`DirectoryFetchError`] and [`CaseCollisions`]
+///
+/// Tasks fetching another location than the current [`AssetBrowserLocation`] are discarded.
 pub(crate) fn poll_task(
     mut commands: Commands,
     mut task_query: Query<(Entity, &mut FetchDirectoryContentTask)>,
+    location: Res<AssetBrowserLocation>,
+    mut directory_content: ResMut<DirectoryContent>,
+    mut entry_details: ResMut<DirectoryEntryDetails>,
+    mut streamed_entries: ResMut<StreamedEntries>,
     content_order: Res<DirectoryContentOrder>,
+    custom_order: Option<Res<CustomDirectoryContentOrder>>,
 ) {
-    let (task_entity, mut task) = task_query.single_mut().unwrap();
-    if let Some(mut content) = block_on(poll_once(&mut task.0)) {
-        content_order.sort(&mut content);
+    for (task_entity, mut task) in task_query.iter_mut() {
+        if task.location != *location {
+            commands.entity(task_entity).despawn();
+            continue;
+        }
+        let result = block_on(poll_once(&mut task.task));
+        // Received after polling, so all the entries sent before the task finished are included
+        let entries: Vec<(Entry, EntryDetails)> = match task.entries.get_mut() {
+            Ok(entries) => entries.try_iter().collect(),
+            Err(_) => Vec::new(),
+        };
+        if !entries.is_empty() || (result.is_some() && !task.streamed) {
+            let content = if task.streamed {
+                streamed_entries.set_changed();
+                directory_content.bypass_change_detection()
+            } else {
+                directory_content.0.clear();
+                entry_details.0.clear();
+                task.streamed = true;
+                directory_content.as_mut()
+            };
+            let entries = entries
+                .into_iter()
+                .map(|(entry, details)| {
+                    if let Entry::Folder(name) | Entry::File(name) = &entry {
+                        entry_details.0.insert(name.clone(), details);
+                    }
+                    entry
+                })
+                .collect();
+            // The content is already sorted, only the new entries have to be sorted in
+            content_order.merge(content, entries, &entry_details, custom_order.as_deref());
+        }
+        let Some((access, fetch_error)) = result else {
+            continue;
+        };
+
+        let case_collisions = directory_content.case_collisions();
+        for names in &case_collisions {
+            eprintln!(
+                "Asset names only differing by case collide on case-insensitive filesystems: {}",
+                names.join(", ")
+            );
+        }
 
         commands.entity(task_entity).despawn();
-        commands.insert_resource(content);
+        commands.insert_resource(CaseCollisions(case_collisions));
+        commands.insert_resource(access);
+        match fetch_error {
+            Some(fetch_error) => {
+                eprintln!(
+                    "Failed to read {}: {}",
+                    fetch_error.path.display(),
+                    fetch_error.message
+                );
+                commands.insert_resource(fetch_error);
+            }
+            None => commands.remove_resource::<DirectoryFetchError>(),
+        }
+        // Refresh the UI with the final result, even if no entry arrived since the last poll
+        directory_content.set_changed();
+    }
+}
+
+/// Fetch the content of the current [`AssetBrowserLocation`] again, unless a fetch is already running
+///
+/// [`poll_task`] marks the [`DirectoryContent`] as changed once the fetch is done, even if it's equal to the
+/// previous content, so the UI is always refreshed.
+pub fn refresh_directory_content(world: &mut World) {
+    match world.run_system_cached(fetch_task_is_running) {
+        Ok(false) => {}
+        Ok(true) => return,
+        Err(e) => {
+            eprintln!("Failed to check if the directory content is being fetched: {e}");
+            return;
+        }
+    }
+    if let Err(e) = world.run_system_cached(fetch_directory_content) {
+        eprintln!("Failed to refresh the directory content: {e}");
     }
 }
 
+/// Check if listing a directory failed because the asset source doesn't have the permission to read it
+pub(crate) fn is_access_denied(error: &AssetReaderError) -> bool {
+    matches!(
+        error,
+        AssetReaderError::Io(io_error) if io_error.kind() == std::io::ErrorKind::PermissionDenied
+    )
+}
+
 /// Spawn a new IO [`FetchDirectoryContentTask`] to fetch the content of the current [`AssetBrowserLocation`]
+///
+/// Hidden entries are skipped unless [`ShowHiddenFiles`] is enabled, see [`IgnorePatterns`].
+/// The [`EntryDetails`] are read from disk inside the Default source.
+/// The tasks still running are cancelled, the new fetch supersedes them.
 pub fn fetch_directory_content(
     mut commands: Commands,
     mut asset_source_builder: ResMut<AssetSourceBuilders>,
     location: Res<AssetBrowserLocation>,
+    default_source_file_path: Res<DefaultSourceFilePath>,
+    show_hidden_files: Res<ShowHiddenFiles>,
+    ignore_patterns: Res<IgnorePatterns>,
+    task_query: Query<Entity, With<FetchDirectoryContentTask>>,
 ) {
+    // Dropping a task cancels it
+    for task_entity in task_query.iter() {
+        commands.entity(task_entity).despawn();
+    }
     let sources = asset_source_builder.build_sources(false, false);
     if location.source_id.is_none() {
+        commands.insert_resource(DirectoryAccess::Granted);
+        commands.remove_resource::<DirectoryFetchError>();
+        commands.insert_resource(CaseCollisions::default());
+        commands.insert_resource(DirectoryEntryDetails::default());
         commands.insert_resource(DirectoryContent(
             sources
                 .iter()
@@ -47,17 +183,31 @@ pub fn fetch_directory_content(
         ));
         return;
     }
+    let task_location = location.clone();
     let location = location.clone();
+    let hidden_patterns = (!show_hidden_files.0).then(|| ignore_patterns.clone());
+    let details_root = (location.source_id == Some(AssetSourceId::Default))
+        .then(|| default_source_file_path.0.join(&location.path));
+    let (sender, entries) = channel();
     let task = IoTaskPool::get().spawn(async move {
         let source = sources.get(location.source_id.unwrap()).unwrap();
         let reader = source.reader();
 
-        let mut content = DirectoryContent::default();
-        let dir_stream = reader.read_directory(location.path.as_path()).await;
-        if dir_stream.is_err() {
-            return content;
-        }
-        let mut dir_stream = dir_stream.unwrap();
+        let mut dir_stream = match reader.read_directory(location.path.as_path()).await {
+            Ok(dir_stream) => dir_stream,
+            Err(error) => {
+                let access = if is_access_denied(&error) {
+                    DirectoryAccess::Denied
+                } else {
+                    DirectoryAccess::Granted
+                };
+                let fetch_error = DirectoryFetchError {
+                    path: location.path.clone(),
+                    message: error.to_string(),
+                };
+                return (access, Some(fetch_error));
+            }
+        };
 
         while let Some(entry) = dir_stream.next().await {
             let entry_name = entry
@@ -67,18 +217,110 @@ pub fn fetch_directory_content(
                 .as_os_str()
                 .to_string_lossy()
                 .to_string();
-            content
-                .0
-                .push(if reader.is_directory(&entry).await.unwrap() {
-                    Entry::Folder(entry_name)
-                } else {
-                    Entry::File(entry_name)
-                });
+            // Skipped before querying the entry type, hidden entries don't cost another read
+            if hidden_patterns
+                .as_ref()
+                .is_some_and(|patterns| patterns.is_hidden(&entry_name))
+            {
+                continue;
+            }
+            let details = details_root
+                .as_ref()
+                .map(|root| EntryDetails::read(&root.join(&entry_name)))
+                .unwrap_or_default();
+            let entry = if reader.is_directory(&entry).await.unwrap() {
+                Entry::Folder(entry_name)
+            } else {
+                Entry::File(entry_name)
+            };
+            // The receiver is only dropped once the task is cancelled
+            let _ = sender.send((entry, details));
         }
-        content
+        (DirectoryAccess::Granted, None)
     });
 
-    commands
-        .spawn_empty()
-        .insert(FetchDirectoryContentTask(task));
+    commands.spawn(FetchDirectoryContentTask {
+        location: task_location,
+        entries: Mutex::new(entries),
+        streamed: false,
+        task,
+    });
+}
+
+#[derive(Component)]
+/// The task that hashes the content of the current source to find [duplicates](DuplicateGroups)
+pub(crate) struct FindDuplicatesTask {
+    task: Task<std::io::Result<Vec<DuplicateGroup>>>,
+    pub(crate) progress: Arc<ScanProgress>,
+}
+
+pub(crate) fn find_duplicates_task_is_running(
+    task_query: Query<(), With<FindDuplicatesTask>>,
+) -> bool {
+    !task_query.is_empty()
+}
+
+/// Poll the [`FindDuplicatesTask`] to check if it's done
+/// If it's done, despawn the task entity and insert the result into [`DuplicateGroups`]
+pub(crate) fn poll_find_duplicates_task(
+    mut commands: Commands,
+    mut task_query: Query<(Entity, &mut FindDuplicatesTask)>,
+) {
+    let (task_entity, mut task) = task_query.single_mut().unwrap();
+    if let Some(result) = block_on(poll_once(&mut task.task)) {
+        commands.entity(task_entity).despawn();
+        match result {
+            Ok(groups) => commands.insert_resource(DuplicateGroups(groups)),
+            Err(e) => {
+                eprintln!("Failed to scan for duplicates: {e}");
+                commands.insert_resource(DuplicateGroups::default());
+            }
+        }
+    }
+}
+
+/// Spawn a new IO [`FindDuplicatesTask`] hashing every file of the Default source
+pub(crate) fn spawn_find_duplicates_task(
+    mut commands: Commands,
+    default_source_file_path: Res<DefaultSourceFilePath>,
+    location: Res<AssetBrowserLocation>,
+    task_query: Query<(), With<FindDuplicatesTask>>,
+) {
+    if location.source_id != Some(AssetSourceId::Default) {
+        eprintln!(
+            "Cannot find duplicates: Invalid source id, make sure your inside the Default source"
+        );
+        return;
+    }
+    if !task_query.is_empty() {
+        return;
+    }
+    let root = default_source_file_path.0.clone();
+    let progress = Arc::new(ScanProgress::default());
+    let task_progress = progress.clone();
+    let task = IoTaskPool::get().spawn(async move { find_duplicates(&root, &task_progress) });
+
+    commands.spawn(FindDuplicatesTask { task, progress });
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+
+    #[test]
+    fn permission_denied_is_access_denied() {
+        let denied = AssetReaderError::Io(Arc::new(std::io::Error::from(
+            std::io::ErrorKind::PermissionDenied,
+        )));
+        assert!(is_access_denied(&denied));
+
+        let not_found = AssetReaderError::NotFound(PathBuf::from("textures/missing"));
+        assert!(!is_access_denied(&not_found));
+        let other = AssetReaderError::Io(Arc::new(std::io::Error::from(
+            std::io::ErrorKind::Interrupted,
+        )));
+        assert!(!is_access_denied(&other));
+    }
 }
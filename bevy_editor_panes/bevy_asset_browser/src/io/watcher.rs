@@ -0,0 +1,108 @@
+//! Watch the directory of the current [`AssetBrowserLocation`] to refetch its content when it's modified on disk
+
+use std::sync::{
+    Mutex,
+    mpsc::{Receiver, channel},
+};
+
+use bevy::{asset::io::AssetSourceId, prelude::*};
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher, event::ModifyKind};
+
+use crate::{AssetBrowserLocation, DefaultSourceFilePath};
+
+use super::task::fetch_directory_content;
+
+/// Watches the directory displayed by the asset browser, only inside the Default source
+#[derive(Resource, Default)]
+pub(crate) struct DirectoryWatcher {
+    /// The watcher of the current directory, it stops watching once dropped
+    _watcher: Option<RecommendedWatcher>,
+    /// The events sent by the watcher
+    events: Option<Mutex<Receiver<notify::Result<Event>>>>,
+}
+
+/// Check if an event changes the entries of the watched directory, not only the content of a file
+fn changes_directory_content(kind: &EventKind) -> bool {
+    matches!(
+        kind,
+        EventKind::Create(_) | EventKind::Remove(_) | EventKind::Modify(ModifyKind::Name(_))
+    )
+}
+
+/// Replace the [`DirectoryWatcher`] with one watching the current [`AssetBrowserLocation`]
+pub(crate) fn watch_location(
+    mut directory_watcher: ResMut<DirectoryWatcher>,
+    location: Res<AssetBrowserLocation>,
+    default_source_file_path: Res<DefaultSourceFilePath>,
+) {
+    // Drop the previous watcher first, so its watch handle is released
+    *directory_watcher = DirectoryWatcher::default();
+    if location.source_id != Some(AssetSourceId::Default) {
+        return;
+    }
+    let path = default_source_file_path.0.join(&location.path);
+    let (sender, receiver) = channel();
+    let mut watcher = match notify::recommended_watcher(sender) {
+        Ok(watcher) => watcher,
+        Err(e) => {
+            eprintln!("Failed to create directory watcher: {e}");
+            return;
+        }
+    };
+    if let Err(e) = watcher.watch(&path, RecursiveMode::NonRecursive) {
+        eprintln!("Failed to watch {}: {e}", path.display());
+        return;
+    }
+    directory_watcher._watcher = Some(watcher);
+    directory_watcher.events = Some(Mutex::new(receiver));
+}
+
+/// Refetch the [`DirectoryContent`](crate::DirectoryContent) when an entry of the watched directory is created, removed or renamed
+///
+/// A fetch already running is cancelled by the new one, see [`fetch_directory_content`].
+pub(crate) fn refetch_on_directory_change(
+    mut commands: Commands,
+    mut directory_watcher: ResMut<DirectoryWatcher>,
+) {
+    let changed = directory_watcher
+        .events
+        .as_mut()
+        .and_then(|events| events.get_mut().ok())
+        .is_some_and(|events| {
+            // Drain all the events, a single refetch covers them
+            events.try_iter().fold(false, |changed, event| match event {
+                Ok(event) => changed || changes_directory_content(&event.kind),
+                Err(e) => {
+                    eprintln!("Directory watcher error: {e}");
+                    changed
+                }
+            })
+        });
+    if changed {
+        commands.run_system_cached(fetch_directory_content);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use notify::event::{CreateKind, DataChange, RemoveKind, RenameMode};
+
+    use super::*;
+
+    #[test]
+    fn only_entry_changes_trigger_a_refetch() {
+        assert!(changes_directory_content(&EventKind::Create(
+            CreateKind::File
+        )));
+        assert!(changes_directory_content(&EventKind::Remove(
+            RemoveKind::Folder
+        )));
+        assert!(changes_directory_content(&EventKind::Modify(
+            ModifyKind::Name(RenameMode::Both)
+        )));
+        assert!(!changes_directory_content(&EventKind::Modify(
+            ModifyKind::Data(DataChange::Content)
+        )));
+        assert!(!changes_directory_content(&EventKind::Any));
+    }
+}
@@ -1,20 +1,33 @@
 //! A UI element for browsing assets in the Bevy Editor.
 /// The intent of this system is to provide a simple and frictionless way to browse assets in the Bevy Editor.
 /// The asset browser is a replica of the your asset directory on disk and get's automatically updated when the directory is modified.
-use std::{cmp::Ordering, path::PathBuf};
+/// Only the directory currently displayed in the Default source is watched, see `io::watcher`.
+use std::{
+    cmp::Ordering,
+    collections::{HashMap, HashSet, VecDeque},
+    ops::Range,
+    path::{Component, Path, PathBuf},
+    time::SystemTime,
+};
 
 use bevy::{
     asset::{
-        AssetPlugin, embedded_asset,
-        io::{AssetSourceId, file::FileAssetReader},
+        AssetPath, AssetPlugin, embedded_asset,
+        io::{AssetSourceBuilders, AssetSourceId, file::FileAssetReader},
     },
+    input_focus::InputFocus,
     prelude::*,
 };
 use bevy_pane_layout::prelude::*;
 use bevy_scroll_box::ScrollBoxPlugin;
+use bevy_text_editing::EditableTextLinePlugin;
+use serde::{Deserialize, Serialize};
 use ui::top_bar::location_as_changed;
 
 mod io;
+pub mod settings;
+#[cfg(test)]
+mod test_utils;
 mod ui;
 
 /// The bevy asset browser plugin
@@ -25,6 +38,9 @@ impl Plugin for AssetBrowserPanePlugin {
         embedded_asset!(app, "assets/directory_icon.png");
         embedded_asset!(app, "assets/source_icon.png");
         embedded_asset!(app, "assets/file_icon.png");
+        embedded_asset!(app, "assets/audio_icon.png");
+        embedded_asset!(app, "assets/text_icon.png");
+        embedded_asset!(app, "assets/archive_icon.png");
 
         app.register_pane("Asset Browser", ui::on_pane_creation);
 
@@ -43,39 +59,192 @@ impl Plugin for AssetBrowserPanePlugin {
             absolute_path
         };
 
+        if !app.is_plugin_added::<EditableTextLinePlugin>() {
+            app.add_plugins(EditableTextLinePlugin);
+        }
+
         app.add_plugins(ScrollBoxPlugin)
             .insert_resource(DefaultSourceFilePath(default_source_absolute_file_path))
+            .insert_resource(settings::AssetBrowserSettingsPath(
+                FileAssetReader::get_base_path().join(settings::SETTINGS_FILE),
+            ))
             .insert_resource(AssetBrowserLocation::default())
             .insert_resource(DirectoryContent::default())
+            .init_resource::<DirectoryEntryDetails>()
             .insert_resource(DirectoryContentOrder::ReverseAlphabetical)
+            .init_resource::<DirectoryAccess>()
+            .init_resource::<CaseCollisions>()
+            .init_resource::<DirectoryFilter>()
+            .init_resource::<DirectoryTypeFilter>()
+            .init_resource::<DuplicateGroups>()
+            .init_resource::<TileSpawnBudget>()
+            .init_resource::<SelectedEntry>()
+            .init_resource::<CheckerboardBackground>()
+            .init_resource::<ExtensionBadges>()
+            .init_resource::<ThumbnailSize>()
+            .init_resource::<BrowserViewMode>()
+            .init_resource::<ShowHiddenFiles>()
+            .init_resource::<IgnorePatterns>()
+            .init_resource::<LocationHistory>()
+            .init_resource::<BrowserBookmarks>()
+            .init_resource::<PreviewIconOverrides>()
+            .init_resource::<PlaceholderIcons>()
+            .init_resource::<RecentAssets>()
+            .init_resource::<Selection>()
+            .init_resource::<ui::selection::SelectionAnchor>()
+            .init_resource::<ui::quick_look::QuickLook>()
+            .init_resource::<io::watcher::DirectoryWatcher>()
+            .init_resource::<io::task::StreamedEntries>()
+            .add_event::<NavigateAssetBrowser>()
             // .init_resource::<DirectoryContentOrder>()
-            .add_systems(Startup, io::task::fetch_directory_content)
+            .add_systems(
+                Startup,
+                (
+                    ui::checkerboard::create_checkerboard_image,
+                    (settings::load_settings, io::task::fetch_directory_content).chain(),
+                ),
+            )
             // .add_systems(Update, button_interaction)
+            .add_systems(
+                Update,
+                navigate_asset_browser.run_if(on_event::<NavigateAssetBrowser>),
+            )
             .add_systems(
                 Update,
                 io::task::poll_task.run_if(io::task::fetch_task_is_running),
             )
             .add_systems(
                 Update,
-                ui::directory_content::refresh_ui
-                    .run_if(directory_content_as_changed)
+                io::watcher::refetch_on_directory_change.after(io::task::poll_task),
+            )
+            .add_systems(
+                Update,
+                (
+                    io::task::poll_find_duplicates_task,
+                    ui::duplicates::update_scan_progress,
+                )
+                    .run_if(io::task::find_duplicates_task_is_running),
+            )
+            .add_systems(
+                Update,
+                ui::duplicates::refresh_ui
+                    .run_if(resource_changed::<DuplicateGroups>)
+                    .after(io::task::poll_find_duplicates_task),
+            )
+            .add_systems(
+                Update,
+                sort_directory_content
+                    .run_if(resource_changed::<DirectoryContentOrder>)
                     .after(io::task::poll_task),
             )
+            .add_systems(
+                Update,
+                ui::directory_content::refresh_ui
+                    .run_if(
+                        directory_content_as_changed
+                            .or(directory_filters_as_changed)
+                            .or(resource_changed::<CheckerboardBackground>)
+                            .or(resource_changed::<ExtensionBadges>)
+                            .or(resource_changed::<PreviewIconOverrides>)
+                            .or(resource_changed::<PlaceholderIcons>)
+                            .or(resource_changed::<BrowserViewMode>),
+                    )
+                    .after(sort_directory_content),
+            )
+            .add_systems(
+                Update,
+                ui::directory_content::refresh_streamed_tiles
+                    .run_if(resource_changed::<io::task::StreamedEntries>)
+                    .after(ui::directory_content::refresh_ui),
+            )
+            .add_systems(
+                Update,
+                (
+                    ui::directory_content::spawn_visible_tiles,
+                    ui::directory_content::place_tiles,
+                )
+                    .chain()
+                    .after(ui::directory_content::refresh_streamed_tiles),
+            )
             .add_systems(
                 Update,
                 (
                     ui::top_bar::refresh_ui,
+                    ui::top_bar::refresh_parent_directory_button,
+                    record_location_history,
                     ui::directory_content::refresh_context_menu,
+                    ui::selection::clear_selection,
+                    io::watcher::watch_location,
                 )
                     .run_if(location_as_changed),
+            )
+            .add_systems(
+                Update,
+                ui::rename::cancel_unfocused_rename
+                    .run_if(resource_exists_and_changed::<InputFocus>),
+            )
+            .add_systems(
+                Update,
+                ui::top_bar::refresh_hidden_files_toggle
+                    .run_if(resource_changed::<ShowHiddenFiles>),
+            )
+            .add_systems(
+                Update,
+                ui::top_bar::refresh_bookmarks.run_if(resource_changed::<BrowserBookmarks>),
+            )
+            .add_systems(
+                Update,
+                ui::top_bar::refresh_view_mode_toggle.run_if(resource_changed::<BrowserViewMode>),
+            )
+            .add_systems(
+                Update,
+                ui::top_bar::refresh_history_buttons
+                    .run_if(resource_changed::<LocationHistory>)
+                    .after(record_location_history),
+            )
+            .add_systems(
+                Update,
+                ui::top_bar::search_shortcuts.before(ui::keyboard_navigation::keyboard_navigation),
+            )
+            .add_systems(
+                Update,
+                (
+                    ui::keyboard_navigation::keyboard_navigation,
+                    ui::keyboard_navigation::highlight_selected_entry,
+                )
+                    .chain()
+                    .after(ui::directory_content::spawn_visible_tiles),
+            )
+            .add_systems(
+                Update,
+                (
+                    ui::thumbnail_size::resize_tiles,
+                    ui::thumbnail_size::update_slider_thumb
+                        .run_if(resource_changed::<ThumbnailSize>),
+                )
+                    .after(ui::directory_content::spawn_visible_tiles),
+            )
+            .add_systems(
+                Update,
+                ui::selection::highlight_selection
+                    .after(ui::directory_content::spawn_visible_tiles),
+            )
+            .add_systems(
+                Update,
+                ui::quick_look::update_quick_look.run_if(ui::quick_look::quick_look_is_active),
             );
     }
 }
 
-fn alphabetical_sort(left: &Entry, right: &Entry) -> Ordering {
+/// Compare entries with `compare_names`, keeping folders ahead of files
+fn folders_first(
+    left: &Entry,
+    right: &Entry,
+    compare_names: impl Fn(&str, &str) -> Ordering,
+) -> Ordering {
     match (left, right) {
         (Entry::Folder(left_name), Entry::Folder(right_name))
-        | (Entry::File(left_name), Entry::File(right_name)) => left_name.cmp(right_name),
+        | (Entry::File(left_name), Entry::File(right_name)) => compare_names(left_name, right_name),
         (Entry::File(_), Entry::Folder(_)) => Ordering::Greater,
         (Entry::Folder(_), Entry::File(_)) => Ordering::Less,
         // TODO: Figure out whether or not ignoring the order of asset sources is a good idea.
@@ -83,36 +252,198 @@ fn alphabetical_sort(left: &Entry, right: &Entry) -> Ordering {
     }
 }
 
+fn alphabetical_sort(left: &Entry, right: &Entry) -> Ordering {
+    folders_first(left, right, Ord::cmp)
+}
+
 fn reverse_alphabetical_sort(left: &Entry, right: &Entry) -> Ordering {
-    match (left, right) {
-        (Entry::Folder(left_name), Entry::Folder(right_name))
-        | (Entry::File(left_name), Entry::File(right_name)) => left_name.cmp(right_name).reverse(),
-        (Entry::File(_), Entry::Folder(_)) => Ordering::Greater,
-        (Entry::Folder(_), Entry::File(_)) => Ordering::Less,
-        // TODO: Figure out whether or not ignoring the order of asset sources is a good idea.
-        _ => Ordering::Equal,
+    folders_first(left, right, |left, right| right.cmp(left))
+}
+
+fn case_insensitive_alphabetical_sort(left: &Entry, right: &Entry) -> Ordering {
+    folders_first(left, right, |left, right| {
+        left.to_lowercase()
+            .cmp(&right.to_lowercase())
+            // Keep names only differing by case in a stable order
+            .then_with(|| left.cmp(right))
+    })
+}
+
+fn natural_sort(left: &Entry, right: &Entry) -> Ordering {
+    folders_first(left, right, natural_cmp)
+}
+
+/// Compare names by extension ignoring case, then alphabetically, names without extension coming first
+fn type_cmp(left: &str, right: &str) -> Ordering {
+    let extension = |name: &str| {
+        Path::new(name)
+            .extension()
+            .map(|extension| extension.to_string_lossy().to_lowercase())
+    };
+    extension(left)
+        .cmp(&extension(right))
+        .then_with(|| left.cmp(right))
+}
+
+/// Split a name into runs of ASCII digits and runs of other characters
+fn natural_runs(name: &str) -> impl Iterator<Item = &str> {
+    let mut rest = name;
+    std::iter::from_fn(move || {
+        let is_digit = rest.chars().next()?.is_ascii_digit();
+        let end = rest
+            .find(|c: char| c.is_ascii_digit() != is_digit)
+            .unwrap_or(rest.len());
+        let (run, tail) = rest.split_at(end);
+        rest = tail;
+        Some(run)
+    })
+}
+
+/// Compare names run by run, numbers being compared by value so `frame_2` comes before `frame_10`
+fn natural_cmp(left: &str, right: &str) -> Ordering {
+    let mut left_runs = natural_runs(left);
+    let mut right_runs = natural_runs(right);
+    loop {
+        let (left_run, right_run) = match (left_runs.next(), right_runs.next()) {
+            // Only zero padding differs, e.g. `frame_01` and `frame_1`
+            (None, None) => return left.cmp(right),
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(left_run), Some(right_run)) => (left_run, right_run),
+        };
+        let is_number = |run: &str| run.starts_with(|c: char| c.is_ascii_digit());
+        let ordering = if is_number(left_run) && is_number(right_run) {
+            // Compare the digits without parsing them, so numbers of any length are supported
+            let left_digits = left_run.trim_start_matches('0');
+            let right_digits = right_run.trim_start_matches('0');
+            left_digits
+                .len()
+                .cmp(&right_digits.len())
+                .then_with(|| left_digits.cmp(right_digits))
+        } else {
+            left_run.cmp(right_run)
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
     }
 }
 
 /// How [`DirectoryContent`] should be ordered
-#[derive(Resource, Default, Debug, Clone, PartialEq, Eq)]
+#[derive(Resource, Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum DirectoryContentOrder {
     /// Ordered alphabetically with respect to folders
     #[default]
     Alphabetical,
     /// Ordered reverse alphabetically with respect to folders
     ReverseAlphabetical,
+    /// Ordered alphabetically ignoring case with respect to folders, so `apple.png` comes before `Zebra.png`
+    CaseInsensitiveAlphabetical,
+    /// Ordered alphabetically comparing numbers by value with respect to folders, so `frame_2` comes before `frame_10`
+    Natural,
+    /// Ordered by the comparator of the [`CustomDirectoryContentOrder`] resource
+    Custom,
+    /// Ordered by extension then alphabetically with respect to folders
+    Type,
+    /// Ordered by extension then alphabetically, in reverse, with respect to folders
+    ReverseType,
+    /// Ordered from the smallest to the largest file with respect to folders, see [`EntryDetails::size`]
+    Size,
+    /// Ordered from the largest to the smallest file with respect to folders
+    ReverseSize,
+    /// Ordered from the oldest to the most recently modified with respect to folders, see [`EntryDetails::modified`]
+    Modified,
+    /// Ordered from the most recently modified to the oldest with respect to folders
+    ReverseModified,
 }
 impl DirectoryContentOrder {
     /// Sorts a given [`DirectoryContent`] with the current method
-    pub fn sort(&self, content: &mut DirectoryContent) {
+    ///
+    /// The size and modification orders read the `details` of the entries, entries without details come first.
+    /// [`DirectoryContentOrder::Custom`] keeps the content untouched if no `custom_order` is given.
+    pub fn sort(
+        &self,
+        content: &mut DirectoryContent,
+        details: &DirectoryEntryDetails,
+        custom_order: Option<&CustomDirectoryContentOrder>,
+    ) {
+        content
+            .0
+            .sort_by(|left, right| self.compare(left, right, details, custom_order));
+    }
+
+    /// Add `entries` to an already sorted [`DirectoryContent`], keeping it sorted
+    ///
+    /// Only the new entries are sorted, then merged with the content in a single pass,
+    /// entries comparing equal keep the existing ones first like [`DirectoryContentOrder::sort`].
+    pub fn merge(
+        &self,
+        content: &mut DirectoryContent,
+        mut entries: Vec<Entry>,
+        details: &DirectoryEntryDetails,
+        custom_order: Option<&CustomDirectoryContentOrder>,
+    ) {
+        entries.sort_by(|left, right| self.compare(left, right, details, custom_order));
+        let existing = std::mem::take(&mut content.0);
+        content.0.reserve(existing.len() + entries.len());
+        let mut existing = existing.into_iter().peekable();
+        let mut entries = entries.into_iter().peekable();
+        while let (Some(old), Some(new)) = (existing.peek(), entries.peek()) {
+            let next = if self.compare(new, old, details, custom_order).is_lt() {
+                entries.next()
+            } else {
+                existing.next()
+            };
+            content.0.extend(next);
+        }
+        content.0.extend(existing.chain(entries));
+    }
+
+    /// Compare two entries with the current method, see [`DirectoryContentOrder::sort`]
+    pub fn compare(
+        &self,
+        left: &Entry,
+        right: &Entry,
+        details: &DirectoryEntryDetails,
+        custom_order: Option<&CustomDirectoryContentOrder>,
+    ) -> Ordering {
         match self {
-            Self::Alphabetical => content.0.sort_by(alphabetical_sort),
-            Self::ReverseAlphabetical => content.0.sort_by(reverse_alphabetical_sort),
+            Self::Alphabetical => alphabetical_sort(left, right),
+            Self::ReverseAlphabetical => reverse_alphabetical_sort(left, right),
+            Self::CaseInsensitiveAlphabetical => case_insensitive_alphabetical_sort(left, right),
+            Self::Natural => natural_sort(left, right),
+            Self::Type => folders_first(left, right, type_cmp),
+            Self::ReverseType => folders_first(left, right, |left, right| type_cmp(right, left)),
+            Self::Size => folders_first(left, right, |left, right| details.size_cmp(left, right)),
+            Self::ReverseSize => {
+                folders_first(left, right, |left, right| details.size_cmp(right, left))
+            }
+            Self::Modified => {
+                folders_first(left, right, |left, right| details.modified_cmp(left, right))
+            }
+            Self::ReverseModified => {
+                folders_first(left, right, |left, right| details.modified_cmp(right, left))
+            }
+            Self::Custom => custom_order.map_or(Ordering::Equal, |custom_order| {
+                (custom_order.0)(left, right)
+            }),
         }
     }
 }
 
+/// A user provided comparator used to sort the [`DirectoryContent`] when [`DirectoryContentOrder::Custom`] is selected
+///
+/// This allows projects to implement domain-specific ordering, e.g. following a manifest.
+#[derive(Resource)]
+pub struct CustomDirectoryContentOrder(pub Box<dyn Fn(&Entry, &Entry) -> Ordering + Send + Sync>);
+
+impl CustomDirectoryContentOrder {
+    /// Create a new custom order from a comparator
+    pub fn new(compare: impl Fn(&Entry, &Entry) -> Ordering + Send + Sync + 'static) -> Self {
+        Self(Box::new(compare))
+    }
+}
+
 /// One entry of [`DirectoryContent`]
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Entry {
@@ -128,13 +459,637 @@ pub enum Entry {
 #[derive(Resource, Default, Debug, Clone, PartialEq, Eq)]
 pub struct DirectoryContent(pub Vec<Entry>);
 
+/// Size and modification date of a folder or file, displayed by the [`BrowserViewMode::Details`] view
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EntryDetails {
+    /// Size in bytes, `None` for folders
+    pub size: Option<u64>,
+    /// Last modification date, `None` if the platform doesn't record it
+    pub modified: Option<SystemTime>,
+}
+
+impl EntryDetails {
+    /// Read the details of the folder or file at `path`, left empty if its metadata can't be read
+    pub fn read(path: &Path) -> Self {
+        let Ok(metadata) = std::fs::metadata(path) else {
+            return Self::default();
+        };
+        Self {
+            size: metadata.is_file().then_some(metadata.len()),
+            modified: metadata.modified().ok(),
+        }
+    }
+}
+
+/// The [`EntryDetails`] of the [`DirectoryContent`] entries, by name
+///
+/// Only the entries of the Default source have details, other sources don't expose their files metadata.
+#[derive(Resource, Default, Debug, Clone, PartialEq, Eq)]
+pub struct DirectoryEntryDetails(pub HashMap<String, EntryDetails>);
+
+impl DirectoryEntryDetails {
+    /// Get the details of a folder or file
+    pub fn get(&self, entry: &Entry) -> Option<&EntryDetails> {
+        match entry {
+            Entry::Folder(name) | Entry::File(name) => self.0.get(name),
+            Entry::Source(_) => None,
+        }
+    }
+
+    /// Compare the sizes of two entries, then their names
+    fn size_cmp(&self, left: &str, right: &str) -> Ordering {
+        let size = |name: &str| self.0.get(name).and_then(|details| details.size);
+        size(left).cmp(&size(right)).then_with(|| left.cmp(right))
+    }
+
+    /// Compare the modification dates of two entries, then their names
+    fn modified_cmp(&self, left: &str, right: &str) -> Ordering {
+        let modified = |name: &str| self.0.get(name).and_then(|details| details.modified);
+        modified(left)
+            .cmp(&modified(right))
+            .then_with(|| left.cmp(right))
+    }
+}
+
+/// Sort the [`DirectoryContent`] again once the [`DirectoryContentOrder`] changes, e.g. from a details view header
+pub(crate) fn sort_directory_content(
+    mut directory_content: ResMut<DirectoryContent>,
+    content_order: Res<DirectoryContentOrder>,
+    details: Res<DirectoryEntryDetails>,
+    custom_order: Option<Res<CustomDirectoryContentOrder>>,
+) {
+    content_order.sort(&mut directory_content, &details, custom_order.as_deref());
+}
+
+impl DirectoryContent {
+    /// Find the [entries](Entry) whose names only differ by case
+    ///
+    /// Such entries collide on case-insensitive filesystems (Windows, macOS), so they are flagged on every platform
+    /// to catch them before the project is shared. Each group is sorted, and groups are sorted by their first name.
+    pub fn case_collisions(&self) -> Vec<Vec<String>> {
+        let mut names_by_folded_case: HashMap<String, Vec<String>> = HashMap::new();
+        for entry in &self.0 {
+            if let Entry::Folder(name) | Entry::File(name) = entry {
+                names_by_folded_case
+                    .entry(name.to_lowercase())
+                    .or_default()
+                    .push(name.clone());
+            }
+        }
+        let mut collisions: Vec<Vec<String>> = names_by_folded_case
+            .into_values()
+            .filter(|names| names.len() > 1)
+            .map(|mut names| {
+                names.sort();
+                names
+            })
+            .collect();
+        collisions.sort();
+        collisions
+    }
+}
+
+/// The groups of [entries](Entry) of the [`DirectoryContent`] whose names only differ by case
+///
+/// See [`DirectoryContent::case_collisions`].
+#[derive(Resource, Default, Debug, Clone, PartialEq, Eq)]
+pub struct CaseCollisions(pub Vec<Vec<String>>);
+
+/// Filter the displayed [`DirectoryContent`] by name
+///
+/// Only the [entries](Entry) whose name contains the query, ignoring case, are displayed.
+/// [Sources](Entry::Source) are always displayed, and an empty query displays everything.
+#[derive(Resource, Default, Debug, Clone, PartialEq, Eq)]
+pub struct DirectoryFilter(pub String);
+
+impl DirectoryFilter {
+    /// Check if an [`Entry`] should be displayed with the current query
+    pub fn matches(&self, entry: &Entry) -> bool {
+        match entry {
+            Entry::Source(_) => true,
+            Entry::Folder(name) | Entry::File(name) => {
+                self.0.is_empty() || name.to_lowercase().contains(&self.0.to_lowercase())
+            }
+        }
+    }
+}
+
+/// Only display the files whose extension is part of an allow-list
+///
+/// [Folders](Entry::Folder) and [sources](Entry::Source) are always displayed so navigation still works.
+#[derive(Resource, Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DirectoryTypeFilter {
+    /// Allowed file extensions, without the leading dot, ignoring case
+    pub extensions: Vec<String>,
+    /// Whether the filter is applied or not
+    pub enabled: bool,
+}
+
+impl DirectoryTypeFilter {
+    /// Check if an [`Entry`] should be displayed with the current allow-list
+    pub fn matches(&self, entry: &Entry) -> bool {
+        let Entry::File(name) = entry else {
+            return true;
+        };
+        if !self.enabled {
+            return true;
+        }
+        let Some(extension) = Path::new(name).extension() else {
+            return false;
+        };
+        let extension = extension.to_string_lossy();
+        self.extensions.iter().any(|allowed| {
+            allowed
+                .trim_start_matches('.')
+                .eq_ignore_ascii_case(&extension)
+        })
+    }
+}
+
+/// Display the hidden entries: dotfiles and the names matching the [`IgnorePatterns`]
+///
+/// Hidden entries are skipped while reading the directory, so the content has to be fetched again once changed.
+#[derive(Resource, Default, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ShowHiddenFiles(pub bool);
+
+/// Patterns of the entry names hidden unless [`ShowHiddenFiles`] is enabled, like `*.meta`
+///
+/// `*` matches any sequence of characters and `?` any single character, ignoring case.
+#[derive(Resource, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IgnorePatterns(pub Vec<String>);
+
+impl Default for IgnorePatterns {
+    fn default() -> Self {
+        Self(vec!["*.meta".to_string(), "*.import".to_string()])
+    }
+}
+
+impl IgnorePatterns {
+    /// Check if an entry called `name` is hidden, being a dotfile or matching one of the patterns
+    pub fn is_hidden(&self, name: &str) -> bool {
+        if name.starts_with('.') {
+            return true;
+        }
+        let name: Vec<char> = name.chars().collect();
+        self.0.iter().any(|pattern| {
+            let pattern: Vec<char> = pattern.chars().collect();
+            wildcard_matches(&pattern, &name)
+        })
+    }
+}
+
+/// Match `name` against a `pattern` made of `*` and `?` wildcards, ignoring ASCII case
+fn wildcard_matches(pattern: &[char], name: &[char]) -> bool {
+    match pattern.split_first() {
+        None => name.is_empty(),
+        Some(('*', rest)) => (0..=name.len()).any(|skip| wildcard_matches(rest, &name[skip..])),
+        Some(('?', rest)) => !name.is_empty() && wildcard_matches(rest, &name[1..]),
+        Some((char, rest)) => name.split_first().is_some_and(|(name_char, name_rest)| {
+            char.eq_ignore_ascii_case(name_char) && wildcard_matches(rest, name_rest)
+        }),
+    }
+}
+
+/// Display a checkerboard behind image tiles and the quick look, so transparency is visible
+#[derive(Resource, Default, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CheckerboardBackground(pub bool);
+
+/// Display the extension of the files as a badge in the corner of their tile
+#[derive(Resource, Default, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExtensionBadges(pub bool);
+
+/// Sizes in pixels the thumbnails of the tiles can be displayed at, from smallest to largest
+pub const THUMBNAIL_SIZES: [u32; 5] = [32, 50, 64, 96, 128];
+
+/// Icons displayed instead of the generic file icon for custom file types, keyed by lowercase extension
+///
+/// Lets a project tell its own formats apart, like `level` or `prefab` files, without rendering a preview for them.
+#[derive(Resource, Default, Debug, Clone)]
+pub struct PreviewIconOverrides(pub HashMap<String, Handle<Image>>);
+
+impl PreviewIconOverrides {
+    /// The icon registered for the extension of `file_name`, if any
+    pub fn icon(&self, file_name: &str) -> Option<&Handle<Image>> {
+        let extension = Path::new(file_name).extension()?.to_str()?;
+        self.0.get(&extension.to_lowercase())
+    }
+}
+
+/// File extensions of the audio files, displayed with the [`FileCategory::Audio`] placeholder
+const AUDIO_EXTENSIONS: [&str; 6] = ["ogg", "oga", "wav", "mp3", "flac", "spx"];
+
+/// File extensions of the plain text files, displayed with the [`FileCategory::Text`] placeholder
+const TEXT_EXTENSIONS: [&str; 10] = [
+    "txt", "md", "ron", "json", "toml", "yaml", "yml", "csv", "wgsl", "glsl",
+];
+
+/// File extensions of the archives, displayed with the [`FileCategory::Archive`] placeholder
+const ARCHIVE_EXTENSIONS: [&str; 6] = ["zip", "tar", "gz", "tgz", "7z", "rar"];
+
+/// Check if the extension of `file_name` is one of `extensions`, ignoring the case
+fn has_extension(file_name: &str, extensions: &[&str]) -> bool {
+    Path::new(file_name)
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| {
+            extensions
+                .iter()
+                .any(|other| extension.eq_ignore_ascii_case(other))
+        })
+}
+
+/// Check if a file is an audio file
+pub(crate) fn is_audio_file(file_name: &str) -> bool {
+    has_extension(file_name, &AUDIO_EXTENSIONS)
+}
+
+/// Kind of file, deduced from its extension, used to pick its [`PlaceholderIcons`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FileCategory {
+    /// Images that can be loaded as their own preview
+    Image,
+    /// Sounds and music
+    Audio,
+    /// Plain text files, like RON scenes, configs and shaders
+    Text,
+    /// Compressed archives
+    Archive,
+    /// Any file whose extension isn't known
+    Unknown,
+}
+
+impl FileCategory {
+    /// The category of `file_name`
+    pub fn from_file_name(file_name: &str) -> Self {
+        if ui::nodes::is_image_file(file_name) {
+            Self::Image
+        } else if is_audio_file(file_name) {
+            Self::Audio
+        } else if has_extension(file_name, &TEXT_EXTENSIONS) {
+            Self::Text
+        } else if has_extension(file_name, &ARCHIVE_EXTENSIONS) {
+            Self::Archive
+        } else {
+            Self::Unknown
+        }
+    }
+}
+
+/// Icons displayed for the files without a [`PreviewIconOverrides`] entry, keyed by [`FileCategory`]
+///
+/// Filled with the embedded icons by default, a category without an icon uses the [`FileCategory::Unknown`] one.
+/// Images inside an asset source are displayed with their own content instead.
+#[derive(Resource, Debug, Clone)]
+pub struct PlaceholderIcons(pub HashMap<FileCategory, Handle<Image>>);
+
+impl FromWorld for PlaceholderIcons {
+    fn from_world(world: &mut World) -> Self {
+        let asset_server = world.resource::<AssetServer>();
+        let file_icon: Handle<Image> =
+            asset_server.load("embedded://bevy_asset_browser/assets/file_icon.png");
+        Self(HashMap::from([
+            (FileCategory::Image, file_icon.clone()),
+            (
+                FileCategory::Audio,
+                asset_server.load("embedded://bevy_asset_browser/assets/audio_icon.png"),
+            ),
+            (
+                FileCategory::Text,
+                asset_server.load("embedded://bevy_asset_browser/assets/text_icon.png"),
+            ),
+            (
+                FileCategory::Archive,
+                asset_server.load("embedded://bevy_asset_browser/assets/archive_icon.png"),
+            ),
+            (FileCategory::Unknown, file_icon),
+        ]))
+    }
+}
+
+impl PlaceholderIcons {
+    /// The placeholder for the category of `file_name`, falling back to the [`FileCategory::Unknown`] one
+    pub fn icon(&self, file_name: &str) -> Option<&Handle<Image>> {
+        self.0
+            .get(&FileCategory::from_file_name(file_name))
+            .or_else(|| self.0.get(&FileCategory::Unknown))
+    }
+}
+
+/// Height in pixels of the thumbnails displayed in the tiles, always one of the [`THUMBNAIL_SIZES`]
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ThumbnailSize(pub u32);
+
+impl Default for ThumbnailSize {
+    fn default() -> Self {
+        Self(50)
+    }
+}
+
+impl ThumbnailSize {
+    /// Snap to the closest of the [`THUMBNAIL_SIZES`]
+    pub fn clamped(self) -> Self {
+        Self(
+            THUMBNAIL_SIZES
+                .into_iter()
+                .min_by_key(|size| size.abs_diff(self.0))
+                .unwrap(),
+        )
+    }
+
+    /// Get the thumbnail size at `fraction` of the slider, from 0 for the smallest size to 1 for the largest
+    pub fn from_slider(fraction: f32) -> Self {
+        let last = THUMBNAIL_SIZES.len() - 1;
+        let index = (fraction.clamp(0.0, 1.0) * last as f32).round() as usize;
+        Self(THUMBNAIL_SIZES[index])
+    }
+
+    /// Get the position of this size on the slider, the inverse of [`ThumbnailSize::from_slider`]
+    pub fn slider_fraction(self) -> f32 {
+        let index = THUMBNAIL_SIZES
+            .iter()
+            .position(|size| *size == self.clamped().0)
+            .unwrap();
+        index as f32 / (THUMBNAIL_SIZES.len() - 1) as f32
+    }
+
+    /// Width and height of a tile, margins excluded, leaving room for the label under the thumbnail
+    pub fn tile_size(self) -> f32 {
+        self.0 as f32 + 50.0
+    }
+
+    /// Width and height of a tile, margins included
+    pub fn tile_outer_size(self) -> f32 {
+        self.tile_size() + 10.0
+    }
+}
+
+/// Height in pixels of a row of the [`BrowserViewMode::Details`] view, margins included
+pub const DETAILS_ROW_HEIGHT: f32 = 22.0;
+
+/// How the displayed [entries](Entry) of the [`DirectoryContent`] are laid out
+#[derive(Resource, Default, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BrowserViewMode {
+    /// Tiles with a thumbnail, sized by the [`ThumbnailSize`]
+    #[default]
+    Grid,
+    /// One row per entry, with columns for the type, size and modification date, see [`EntryDetails`]
+    Details,
+}
+
+impl BrowserViewMode {
+    /// Number of entries per row, for a content `content_width` pixels wide
+    pub fn columns(self, content_width: f32, thumbnail_size: ThumbnailSize) -> usize {
+        match self {
+            Self::Grid => ((content_width / thumbnail_size.tile_outer_size()) as usize).max(1),
+            Self::Details => 1,
+        }
+    }
+
+    /// Height of a row of entries, margins included, which is also the width of a grid cell
+    pub fn cell_size(self, thumbnail_size: ThumbnailSize) -> f32 {
+        match self {
+            Self::Grid => thumbnail_size.tile_outer_size(),
+            Self::Details => DETAILS_ROW_HEIGHT,
+        }
+    }
+}
+
+/// The displayed [entry](Entry) selected with the keyboard, as an index among the entries passing the filters
+///
+/// Reset whenever the displayed entries change, entries streamed in keep it on the same entry.
+#[derive(Resource, Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SelectedEntry(pub Option<usize>);
+
+impl SelectedEntry {
+    /// Move the selection by `offset` entries, clamped to the `displayed` entries count
+    ///
+    /// Without a selection, the first entry gets selected.
+    pub fn move_by(&mut self, offset: isize, displayed: usize) {
+        if displayed == 0 {
+            self.0 = None;
+            return;
+        }
+        self.0 = Some(match self.0 {
+            None => 0,
+            Some(index) => index.saturating_add_signed(offset).min(displayed - 1),
+        });
+    }
+}
+
+/// The paths of the selected [entries](Entry), relative to the current asset source root
+///
+/// Cleared whenever the [`AssetBrowserLocation`] changes.
+#[derive(Resource, Default, Debug, Clone, PartialEq, Eq)]
+pub struct Selection(pub HashSet<PathBuf>);
+
+/// How clicking an entry modifies the [`Selection`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SelectionMode {
+    /// Only select the clicked entry
+    Replace,
+    /// Add or remove the clicked entry, with Ctrl
+    Toggle,
+    /// Select all the entries between the last clicked entry and the clicked one, with Shift
+    Range,
+}
+
+impl SelectionMode {
+    /// Get the selection mode from the held modifier keys
+    pub fn from_keys(keys: &ButtonInput<KeyCode>) -> Self {
+        if keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]) {
+            Self::Range
+        } else if keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]) {
+            Self::Toggle
+        } else {
+            Self::Replace
+        }
+    }
+}
+
+impl Selection {
+    /// Apply a click on the `clicked` index of the `displayed` entries paths, in their current order
+    ///
+    /// `anchor` is the index of the last entry clicked without Shift, used as the start of [`SelectionMode::Range`].
+    /// Returns the new anchor.
+    pub fn click(
+        &mut self,
+        mode: SelectionMode,
+        clicked: usize,
+        anchor: Option<usize>,
+        displayed: &[PathBuf],
+    ) -> Option<usize> {
+        let Some(clicked_path) = displayed.get(clicked) else {
+            return anchor;
+        };
+        match mode {
+            SelectionMode::Replace => {
+                self.0.clear();
+                self.0.insert(clicked_path.clone());
+                Some(clicked)
+            }
+            SelectionMode::Toggle => {
+                if !self.0.remove(clicked_path) {
+                    self.0.insert(clicked_path.clone());
+                }
+                Some(clicked)
+            }
+            SelectionMode::Range => {
+                let anchor = anchor.unwrap_or(clicked).min(displayed.len() - 1);
+                self.0.clear();
+                self.0.extend(
+                    displayed[anchor.min(clicked)..=anchor.max(clicked)]
+                        .iter()
+                        .cloned(),
+                );
+                Some(anchor)
+            }
+        }
+    }
+}
+
+/// Check if the [`DirectoryFilter`] or the [`DirectoryTypeFilter`] has changed, which means the displayed content need to be refreshed
+pub(crate) fn directory_filters_as_changed(
+    directory_filter: Res<DirectoryFilter>,
+    directory_type_filter: Res<DirectoryTypeFilter>,
+) -> bool {
+    directory_filter.is_changed() || directory_type_filter.is_changed()
+}
+
+/// The files most recently opened in the asset browser, the most recent first
+///
+/// A file is opened by double-clicking its tile, or by pressing Enter while it's the [`SelectedEntry`].
+/// Opening a file again moves it to the front, and the oldest files are forgotten past the capacity.
+#[derive(Resource, Debug, Clone, PartialEq, Eq)]
+pub struct RecentAssets {
+    /// The opened files, the most recent first
+    assets: VecDeque<AssetPath<'static>>,
+    /// Maximum number of files remembered
+    capacity: usize,
+}
+
+impl Default for RecentAssets {
+    fn default() -> Self {
+        Self::with_capacity(20)
+    }
+}
+
+impl RecentAssets {
+    /// Remember at most `capacity` files
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            assets: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    /// Maximum number of files remembered
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Change the maximum number of files remembered, forgetting the oldest ones that no longer fit
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+        self.assets.truncate(capacity);
+    }
+
+    /// Record that `asset` was opened, moving it to the front if it was already there
+    pub fn push(&mut self, asset: AssetPath<'static>) {
+        self.assets.retain(|recent| *recent != asset);
+        self.assets.push_front(asset);
+        self.assets.truncate(self.capacity);
+    }
+
+    /// Iterate over the opened files, the most recent first
+    pub fn iter(&self) -> impl Iterator<Item = &AssetPath<'static>> {
+        self.assets.iter()
+    }
+}
+
+/// Maximum number of directory content tiles spawned per frame
+///
+/// Opening a directory with a lot of entries spreads the spawning of their tiles over multiple frames
+/// instead of spiking the frame time.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TileSpawnBudget(pub usize);
+
+impl Default for TileSpawnBudget {
+    fn default() -> Self {
+        Self(100)
+    }
+}
+
+impl TileSpawnBudget {
+    /// The sorted indices of the tiles to spawn this frame: the ones of the visible `range` that aren't `spawned` yet
+    ///
+    /// A budget of 0 still spawns one tile per frame so the content is eventually displayed.
+    pub fn next_batch(&self, range: Range<usize>, spawned: &HashSet<usize>) -> Vec<usize> {
+        range
+            .filter(|index| !spawned.contains(index))
+            .take(self.0.max(1))
+            .collect()
+    }
+}
+
+/// A set of files sharing the exact same content
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DuplicateGroup {
+    /// Size in bytes of each file of the group
+    pub size: u64,
+    /// Path of each file of the group, relative to the asset source root
+    pub paths: Vec<PathBuf>,
+}
+
+/// The duplicate files found by the last "Find Duplicates" scan of the current source
+#[derive(Resource, Default, Debug, Clone, PartialEq, Eq)]
+pub struct DuplicateGroups(pub Vec<DuplicateGroup>);
+
+impl DuplicateGroups {
+    /// Check if the file at `path`, relative to the source root, belongs to a group
+    pub fn contains(&self, path: &Path) -> bool {
+        self.0
+            .iter()
+            .any(|group| group.paths.iter().any(|other| other == path))
+    }
+
+    /// Forget the file at `path`, dropping the groups left with a single file
+    pub fn remove(&mut self, path: &Path) {
+        for group in self.0.iter_mut() {
+            group.paths.retain(|other| other != path);
+        }
+        self.0.retain(|group| group.paths.len() > 1);
+    }
+}
+
+/// Whether the directory pointed by [`AssetBrowserLocation`] could be read
+#[derive(Resource, Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DirectoryAccess {
+    /// The directory content is readable
+    #[default]
+    Granted,
+    /// The asset source refused to list the directory content
+    Denied,
+}
+
+/// Why the directory pointed by [`AssetBrowserLocation`] couldn't be read, only present after a failed fetch
+///
+/// The directory may have been deleted or moved by another program while being browsed.
+#[derive(Resource, Debug, Clone, PartialEq, Eq)]
+pub struct DirectoryFetchError {
+    /// The path of the directory, relative to the asset source root
+    pub path: PathBuf,
+    /// The error reported by the asset source
+    pub message: String,
+}
+
 /// Check if the [`DirectoryContent`] has changed, which relate to the content of the current [`AssetBrowserLocation`]
 pub(crate) fn directory_content_as_changed(directory_content: Res<DirectoryContent>) -> bool {
     directory_content.is_changed()
 }
 
-#[derive(Resource)]
-struct DefaultSourceFilePath(pub PathBuf);
+/// The absolute path on disk of the [default asset source](AssetSourceId::Default) root
+#[derive(Resource, Debug, Clone, PartialEq, Eq)]
+pub struct DefaultSourceFilePath(pub PathBuf);
 
 /// System Set to set up the Asset Browser.
 #[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
@@ -149,11 +1104,1151 @@ pub struct AssetBrowserLocation {
     pub path: PathBuf,
 }
 
+impl AssetBrowserLocation {
+    /// The location listing all the registered asset sources
+    pub fn sources_root() -> Self {
+        Self {
+            source_id: None,
+            path: PathBuf::new(),
+        }
+    }
+
+    /// The root directory of an asset source
+    pub fn source_root(source_id: AssetSourceId<'static>) -> Self {
+        Self {
+            source_id: Some(source_id),
+            path: PathBuf::new(),
+        }
+    }
+
+    /// Get the absolute path on disk of an [`Entry`] of this location
+    ///
+    /// Only the [default asset source](AssetSourceId::Default) is known to be on disk,
+    /// `None` is returned for entries of other sources.
+    pub fn absolute_path(
+        &self,
+        entry: &Entry,
+        default_source_file_path: &DefaultSourceFilePath,
+    ) -> Option<PathBuf> {
+        match (entry, &self.source_id) {
+            (Entry::Source(AssetSourceId::Default), _) => Some(default_source_file_path.0.clone()),
+            (Entry::Folder(name) | Entry::File(name), Some(AssetSourceId::Default)) => {
+                Some(default_source_file_path.0.join(&self.path).join(name))
+            }
+            _ => None,
+        }
+    }
+
+    /// Get the [`AssetPath`] of an [`Entry`] of this location, as given to [`AssetServer::load`]
+    ///
+    /// Sources resolve to their root, `None` is returned for entries listed outside of a source.
+    pub fn asset_path(&self, entry: &Entry) -> Option<AssetPath<'static>> {
+        let (source_id, path) = match (entry, &self.source_id) {
+            (Entry::Source(source_id), _) => (source_id, PathBuf::new()),
+            (Entry::Folder(name) | Entry::File(name), Some(source_id)) => {
+                (source_id, self.path.join(name))
+            }
+            _ => return None,
+        };
+        Some(AssetPath::from_path_buf(path).with_source(source_id.clone()))
+    }
+}
+
 impl Default for AssetBrowserLocation {
     fn default() -> Self {
-        Self {
+        Self::source_root(AssetSourceId::Default)
+    }
+}
+
+/// The previously visited [locations](AssetBrowserLocation), to go back and forward between them
+///
+/// Updated whenever the [`AssetBrowserLocation`] changes, going back or forward only moves locations between the stacks.
+#[derive(Resource, Default, Debug, Clone, PartialEq, Eq)]
+pub struct LocationHistory {
+    /// Locations visited before the current one, the most recent last
+    pub back: Vec<AssetBrowserLocation>,
+    /// Locations left by going back, the most recent last
+    pub forward: Vec<AssetBrowserLocation>,
+    /// The location the history was last updated with
+    current: Option<AssetBrowserLocation>,
+}
+
+impl LocationHistory {
+    /// Record a visit to `location`, the forward stack is cleared when it's a new location
+    pub fn visit(&mut self, location: &AssetBrowserLocation) {
+        match self.current.replace(location.clone()) {
+            Some(previous) if previous != *location => {
+                self.back.push(previous);
+                self.forward.clear();
+            }
+            _ => {}
+        }
+    }
+
+    /// Move to the previous location, returning it if there is one
+    pub fn go_back(&mut self) -> Option<AssetBrowserLocation> {
+        let previous = self.back.pop()?;
+        self.forward.extend(self.current.replace(previous.clone()));
+        Some(previous)
+    }
+
+    /// Move to the location left by [`LocationHistory::go_back`], returning it if there is one
+    pub fn go_forward(&mut self) -> Option<AssetBrowserLocation> {
+        let next = self.forward.pop()?;
+        self.back.extend(self.current.replace(next.clone()));
+        Some(next)
+    }
+}
+
+/// Record the current [`AssetBrowserLocation`] in the [`LocationHistory`]
+fn record_location_history(
+    location: Res<AssetBrowserLocation>,
+    mut history: ResMut<LocationHistory>,
+) {
+    history.visit(&location);
+}
+
+/// Folders bookmarked by the user, displayed in the top bar to jump back to them
+///
+/// Each bookmark is a label followed by the source id and path of its [`AssetBrowserLocation`].
+#[derive(Resource, Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(from = "Vec<SerializedBookmark>", into = "Vec<SerializedBookmark>")]
+pub struct BrowserBookmarks(pub Vec<(String, Option<AssetSourceId<'static>>, PathBuf)>);
+
+impl BrowserBookmarks {
+    /// Bookmark `location` under `label`, only renaming the bookmark if the location is already bookmarked
+    pub fn add(&mut self, label: impl Into<String>, location: &AssetBrowserLocation) {
+        let label = label.into();
+        match self.position(location) {
+            Some(index) => self.0[index].0 = label,
+            None => self
+                .0
+                .push((label, location.source_id.clone(), location.path.clone())),
+        }
+    }
+
+    /// Remove the bookmark of `location`, returning whether it was bookmarked
+    pub fn remove(&mut self, location: &AssetBrowserLocation) -> bool {
+        let Some(index) = self.position(location) else {
+            return false;
+        };
+        self.0.remove(index);
+        true
+    }
+
+    /// Check if `location` is bookmarked
+    pub fn contains(&self, location: &AssetBrowserLocation) -> bool {
+        self.position(location).is_some()
+    }
+
+    /// The location pointed by the bookmark at `index`
+    pub fn location(&self, index: usize) -> Option<AssetBrowserLocation> {
+        self.0
+            .get(index)
+            .map(|(_, source_id, path)| AssetBrowserLocation {
+                source_id: source_id.clone(),
+                path: path.clone(),
+            })
+    }
+
+    /// The label given to a new bookmark: the name of its folder, or of its source at the source root
+    pub fn default_label(location: &AssetBrowserLocation) -> String {
+        match (&location.source_id, location.path.file_name()) {
+            (_, Some(name)) => name.to_string_lossy().into_owned(),
+            (Some(source_id), None) => ui::source_id_to_string(source_id),
+            (None, None) => "Sources".to_string(),
+        }
+    }
+
+    fn position(&self, location: &AssetBrowserLocation) -> Option<usize> {
+        self.0.iter().position(|(_, source_id, path)| {
+            *source_id == location.source_id && *path == location.path
+        })
+    }
+}
+
+/// A [`BrowserBookmarks`] entry as written in the settings, [`AssetSourceId`] itself isn't serializable
+#[derive(Clone, Serialize, Deserialize)]
+struct SerializedBookmark {
+    label: String,
+    /// `None` for the sources root, `Some(None)` for the Default source
+    source: Option<Option<String>>,
+    path: PathBuf,
+}
+
+impl From<Vec<SerializedBookmark>> for BrowserBookmarks {
+    fn from(bookmarks: Vec<SerializedBookmark>) -> Self {
+        Self(
+            bookmarks
+                .into_iter()
+                .map(|bookmark| {
+                    (
+                        bookmark.label,
+                        bookmark.source.map(AssetSourceId::new),
+                        bookmark.path,
+                    )
+                })
+                .collect(),
+        )
+    }
+}
+
+impl From<BrowserBookmarks> for Vec<SerializedBookmark> {
+    fn from(bookmarks: BrowserBookmarks) -> Self {
+        bookmarks
+            .0
+            .into_iter()
+            .map(|(label, source_id, path)| SerializedBookmark {
+                label,
+                source: source_id.map(|source_id| match source_id {
+                    AssetSourceId::Default => None,
+                    AssetSourceId::Name(name) => Some(name.to_string()),
+                }),
+                path,
+            })
+            .collect()
+    }
+}
+
+/// Event other panes can send to move the asset browser to another location
+///
+/// Invalid locations, pointing to an unknown source or outside of the source root, are ignored.
+#[derive(Event, BufferedEvent, Debug, Clone, PartialEq, Eq)]
+pub struct NavigateAssetBrowser {
+    /// The source id of the asset source to browse, `None` to list all the sources
+    pub source_id: Option<AssetSourceId<'static>>,
+    /// The path of the directory to browse relative to the asset source root
+    pub path: PathBuf,
+}
+
+impl NavigateAssetBrowser {
+    /// Check if the path stays inside the asset source root
+    pub fn is_path_valid(&self) -> bool {
+        match self.source_id {
+            None => self.path.as_os_str().is_empty(),
+            Some(_) => self
+                .path
+                .components()
+                .all(|component| matches!(component, Component::Normal(_) | Component::CurDir)),
+        }
+    }
+}
+
+/// Move the [`AssetBrowserLocation`] to the last valid [`NavigateAssetBrowser`] location and fetch its content
+pub(crate) fn navigate_asset_browser(
+    mut commands: Commands,
+    mut events: EventReader<NavigateAssetBrowser>,
+    mut asset_source_builder: ResMut<AssetSourceBuilders>,
+    mut location: ResMut<AssetBrowserLocation>,
+) {
+    let sources = asset_source_builder.build_sources(false, false);
+    let Some(navigation) = events
+        .read()
+        .filter(|navigation| {
+            let source_exists = navigation
+                .source_id
+                .as_ref()
+                .is_none_or(|source_id| sources.get(source_id.clone()).is_ok());
+            if !source_exists || !navigation.is_path_valid() {
+                eprintln!("Ignoring invalid asset browser navigation to {navigation:?}");
+                return false;
+            }
+            true
+        })
+        .last()
+    else {
+        return;
+    };
+    location.source_id = navigation.source_id.clone();
+    location.path = navigation.path.clone();
+    commands.run_system_cached(io::task::fetch_directory_content);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::TempDir;
+
+    #[test]
+    fn custom_order_sorts_with_comparator() {
+        let priorities: HashMap<String, u32> = [("level.ron", 0), ("player.png", 1), ("props", 2)]
+            .into_iter()
+            .map(|(name, priority)| (name.to_string(), priority))
+            .collect();
+        let custom_order = CustomDirectoryContentOrder::new(move |left, right| {
+            let priority = |entry: &Entry| match entry {
+                Entry::Folder(name) | Entry::File(name) => {
+                    priorities.get(name).copied().unwrap_or(u32::MAX)
+                }
+                Entry::Source(_) => u32::MAX,
+            };
+            priority(left).cmp(&priority(right))
+        });
+
+        let mut content = DirectoryContent(vec![
+            Entry::Folder("props".to_string()),
+            Entry::File("unknown.txt".to_string()),
+            Entry::File("player.png".to_string()),
+            Entry::File("level.ron".to_string()),
+        ]);
+        DirectoryContentOrder::Custom.sort(
+            &mut content,
+            &DirectoryEntryDetails::default(),
+            Some(&custom_order),
+        );
+        assert_eq!(
+            content.0,
+            vec![
+                Entry::File("level.ron".to_string()),
+                Entry::File("player.png".to_string()),
+                Entry::Folder("props".to_string()),
+                Entry::File("unknown.txt".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn case_insensitive_order_ignores_case() {
+        let mut content = DirectoryContent(vec![
+            Entry::File("Zebra.png".to_string()),
+            Entry::Folder("textures".to_string()),
+            Entry::File("apple.png".to_string()),
+            Entry::Folder("Audio".to_string()),
+            Entry::File("Apple.png".to_string()),
+        ]);
+        DirectoryContentOrder::CaseInsensitiveAlphabetical.sort(
+            &mut content,
+            &DirectoryEntryDetails::default(),
+            None,
+        );
+        assert_eq!(
+            content.0,
+            vec![
+                Entry::Folder("Audio".to_string()),
+                Entry::Folder("textures".to_string()),
+                Entry::File("Apple.png".to_string()),
+                Entry::File("apple.png".to_string()),
+                Entry::File("Zebra.png".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn natural_order_compares_numbers_by_value() {
+        let files = |names: &[&str]| -> Vec<Entry> {
+            names
+                .iter()
+                .map(|name| Entry::File(name.to_string()))
+                .collect()
+        };
+        let mut content = DirectoryContent(files(&[
+            "frame_10.png",
+            "frame_2.png",
+            "frame_1.png",
+            "frame_9b.png",
+        ]));
+        DirectoryContentOrder::Natural.sort(&mut content, &DirectoryEntryDetails::default(), None);
+        assert_eq!(
+            content.0,
+            files(&["frame_1.png", "frame_2.png", "frame_9b.png", "frame_10.png"])
+        );
+
+        let mut content = DirectoryContent(files(&[
+            "frame_010.png",
+            "frame_002.png",
+            "frame_2.png",
+            "frame_001.png",
+            "frame_0100.png",
+        ]));
+        DirectoryContentOrder::Natural.sort(&mut content, &DirectoryEntryDetails::default(), None);
+        assert_eq!(
+            content.0,
+            files(&[
+                "frame_001.png",
+                "frame_002.png",
+                "frame_2.png",
+                "frame_010.png",
+                "frame_0100.png",
+            ])
+        );
+
+        let mut content = DirectoryContent(vec![
+            Entry::File("level_2.ron".to_string()),
+            Entry::Folder("level_10".to_string()),
+            Entry::Folder("level_9".to_string()),
+        ]);
+        DirectoryContentOrder::Natural.sort(&mut content, &DirectoryEntryDetails::default(), None);
+        assert_eq!(
+            content.0,
+            vec![
+                Entry::Folder("level_9".to_string()),
+                Entry::Folder("level_10".to_string()),
+                Entry::File("level_2.ron".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn merged_entries_match_a_full_sort() {
+        let details = DirectoryEntryDetails(
+            [("b.png", 20), ("a.ron", 300), ("c.png", 1), ("d.txt", 20)]
+                .into_iter()
+                .map(|(name, size)| {
+                    let details = EntryDetails {
+                        size: Some(size),
+                        modified: None,
+                    };
+                    (name.to_string(), details)
+                })
+                .collect(),
+        );
+        let existing = [
+            Entry::File("b.png".to_string()),
+            Entry::Folder("textures".to_string()),
+            Entry::File("a.ron".to_string()),
+        ];
+        let streamed = [
+            Entry::File("d.txt".to_string()),
+            Entry::Folder("audio".to_string()),
+            Entry::File("c.png".to_string()),
+        ];
+        for order in [
+            DirectoryContentOrder::Alphabetical,
+            DirectoryContentOrder::ReverseAlphabetical,
+            DirectoryContentOrder::Natural,
+            DirectoryContentOrder::Type,
+            DirectoryContentOrder::ReverseType,
+            DirectoryContentOrder::Size,
+            DirectoryContentOrder::ReverseSize,
+        ] {
+            let mut merged = DirectoryContent(existing.to_vec());
+            order.sort(&mut merged, &details, None);
+            order.merge(&mut merged, streamed.to_vec(), &details, None);
+            let mut sorted = DirectoryContent([existing, streamed].concat());
+            order.sort(&mut sorted, &details, None);
+            assert_eq!(merged, sorted, "{order:?}");
+        }
+    }
+
+    #[test]
+    fn detail_orders_keep_folders_first() {
+        let second = SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(1);
+        let details = DirectoryEntryDetails(
+            [
+                ("textures", None, Some(second)),
+                ("audio", None, Some(SystemTime::UNIX_EPOCH)),
+                ("level.ron", Some(300), Some(SystemTime::UNIX_EPOCH)),
+                ("player.png", Some(2_000), Some(second)),
+                ("enemy.PNG", Some(50), None),
+            ]
+            .into_iter()
+            .map(|(name, size, modified)| (name.to_string(), EntryDetails { size, modified }))
+            .collect(),
+        );
+        let sorted = |order: DirectoryContentOrder| {
+            let mut content = DirectoryContent(vec![
+                Entry::File("player.png".to_string()),
+                Entry::Folder("textures".to_string()),
+                Entry::File("enemy.PNG".to_string()),
+                Entry::File("README".to_string()),
+                Entry::Folder("audio".to_string()),
+                Entry::File("level.ron".to_string()),
+            ]);
+            order.sort(&mut content, &details, None);
+            content
+                .0
+                .into_iter()
+                .map(|entry| match entry {
+                    Entry::Folder(name) | Entry::File(name) => name,
+                    Entry::Source(_) => unreachable!(),
+                })
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            sorted(DirectoryContentOrder::Type),
+            [
+                "audio",
+                "textures",
+                "README",
+                "enemy.PNG",
+                "player.png",
+                "level.ron"
+            ]
+        );
+        assert_eq!(
+            sorted(DirectoryContentOrder::ReverseType),
+            [
+                "textures",
+                "audio",
+                "level.ron",
+                "player.png",
+                "enemy.PNG",
+                "README"
+            ]
+        );
+        // Files without details, like one created since the fetch, come first
+        assert_eq!(
+            sorted(DirectoryContentOrder::Size),
+            [
+                "audio",
+                "textures",
+                "README",
+                "enemy.PNG",
+                "level.ron",
+                "player.png"
+            ]
+        );
+        assert_eq!(
+            sorted(DirectoryContentOrder::ReverseSize),
+            [
+                "textures",
+                "audio",
+                "player.png",
+                "level.ron",
+                "enemy.PNG",
+                "README"
+            ]
+        );
+        assert_eq!(
+            sorted(DirectoryContentOrder::Modified),
+            [
+                "audio",
+                "textures",
+                "README",
+                "enemy.PNG",
+                "level.ron",
+                "player.png"
+            ]
+        );
+        assert_eq!(
+            sorted(DirectoryContentOrder::ReverseModified),
+            [
+                "textures",
+                "audio",
+                "player.png",
+                "level.ron",
+                "enemy.PNG",
+                "README"
+            ]
+        );
+    }
+
+    #[test]
+    fn hidden_entries_match_dotfiles_and_patterns() {
+        let patterns = IgnorePatterns(vec![
+            "*.meta".to_string(),
+            "thumbs.db".to_string(),
+            "backup_?.ron".to_string(),
+        ]);
+        assert!(patterns.is_hidden(".DS_Store"));
+        assert!(patterns.is_hidden("player.png.meta"));
+        assert!(patterns.is_hidden("Player.PNG.META"));
+        assert!(patterns.is_hidden("Thumbs.db"));
+        assert!(patterns.is_hidden("backup_1.ron"));
+        assert!(!patterns.is_hidden("backup_12.ron"));
+        assert!(!patterns.is_hidden("player.png"));
+        assert!(!patterns.is_hidden("metadata.ron"));
+        assert!(IgnorePatterns(Vec::new()).is_hidden(".gitignore"));
+    }
+
+    #[test]
+    fn case_collisions_are_detected() {
+        let content = DirectoryContent(vec![
+            Entry::Source(AssetSourceId::Default),
+            Entry::File("Texture.png".to_string()),
+            Entry::Folder("Models".to_string()),
+            Entry::File("texture.png".to_string()),
+            Entry::File("level.ron".to_string()),
+            Entry::File("models".to_string()),
+            Entry::File("TEXTURE.PNG".to_string()),
+        ]);
+        assert_eq!(
+            content.case_collisions(),
+            vec![
+                vec!["Models".to_string(), "models".to_string()],
+                vec![
+                    "TEXTURE.PNG".to_string(),
+                    "Texture.png".to_string(),
+                    "texture.png".to_string(),
+                ],
+            ]
+        );
+        assert!(
+            DirectoryContent(vec![Entry::File("level.ron".to_string())])
+                .case_collisions()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn selected_entry_is_clamped() {
+        let mut selected_entry = SelectedEntry::default();
+        selected_entry.move_by(4, 10);
+        assert_eq!(selected_entry, SelectedEntry(Some(0)));
+        selected_entry.move_by(4, 10);
+        assert_eq!(selected_entry, SelectedEntry(Some(4)));
+        selected_entry.move_by(-8, 10);
+        assert_eq!(selected_entry, SelectedEntry(Some(0)));
+        selected_entry.move_by(20, 10);
+        assert_eq!(selected_entry, SelectedEntry(Some(9)));
+        selected_entry.move_by(1, 0);
+        assert_eq!(selected_entry, SelectedEntry(None));
+    }
+
+    #[test]
+    fn selection_click_modes() {
+        let displayed: Vec<PathBuf> = ["a", "b", "c", "d", "e"].map(PathBuf::from).to_vec();
+        let selected = |selection: &Selection| {
+            let mut paths: Vec<&PathBuf> = selection.0.iter().collect();
+            paths.sort();
+            paths
+                .into_iter()
+                .map(|path| path.to_string_lossy().to_string())
+                .collect::<Vec<_>>()
+        };
+        let mut selection = Selection::default();
+
+        let anchor = selection.click(SelectionMode::Replace, 1, None, &displayed);
+        assert_eq!(selected(&selection), ["b"]);
+        let anchor = selection.click(SelectionMode::Toggle, 3, anchor, &displayed);
+        assert_eq!(selected(&selection), ["b", "d"]);
+        let anchor = selection.click(SelectionMode::Toggle, 1, anchor, &displayed);
+        assert_eq!(selected(&selection), ["d"]);
+        let anchor = selection.click(SelectionMode::Range, 0, anchor, &displayed);
+        assert_eq!(selected(&selection), ["a", "b", "c", "d"]);
+        // The anchor is kept so the range can be adjusted
+        let anchor = selection.click(SelectionMode::Range, 4, anchor, &displayed);
+        assert_eq!(selected(&selection), ["d", "e"]);
+        selection.click(SelectionMode::Replace, 2, anchor, &displayed);
+        assert_eq!(selected(&selection), ["c"]);
+    }
+
+    #[test]
+    fn absolute_path_of_entries() {
+        let default_source_file_path = DefaultSourceFilePath(PathBuf::from("/project/assets"));
+        let root = AssetBrowserLocation::default();
+        assert_eq!(
+            root.absolute_path(
+                &Entry::File("player.png".to_string()),
+                &default_source_file_path
+            ),
+            Some(PathBuf::from("/project/assets/player.png"))
+        );
+        let nested = AssetBrowserLocation {
             source_id: Some(AssetSourceId::Default),
-            path: PathBuf::from(""),
+            path: PathBuf::from("textures/characters"),
+        };
+        assert_eq!(
+            nested.absolute_path(
+                &Entry::Folder("hero".to_string()),
+                &default_source_file_path
+            ),
+            Some(PathBuf::from("/project/assets/textures/characters/hero"))
+        );
+
+        let named = AssetBrowserLocation {
+            source_id: Some(AssetSourceId::Name("remote".into())),
+            path: PathBuf::from("textures"),
+        };
+        assert_eq!(
+            named.absolute_path(
+                &Entry::File("player.png".to_string()),
+                &default_source_file_path
+            ),
+            None
+        );
+        let sources = AssetBrowserLocation {
+            source_id: None,
+            path: PathBuf::new(),
+        };
+        assert_eq!(
+            sources.absolute_path(
+                &Entry::Source(AssetSourceId::Default),
+                &default_source_file_path
+            ),
+            Some(PathBuf::from("/project/assets"))
+        );
+        assert_eq!(
+            sources.absolute_path(
+                &Entry::Source(AssetSourceId::Name("remote".into())),
+                &default_source_file_path
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn asset_path_of_entries() {
+        let nested = AssetBrowserLocation {
+            source_id: Some(AssetSourceId::Default),
+            path: PathBuf::from("textures/characters"),
+        };
+        assert_eq!(
+            nested
+                .asset_path(&Entry::File("hero.png".to_string()))
+                .unwrap()
+                .to_string(),
+            "textures/characters/hero.png"
+        );
+        let named = AssetBrowserLocation {
+            source_id: Some(AssetSourceId::Name("remote".into())),
+            path: PathBuf::from("textures"),
+        };
+        assert_eq!(
+            named
+                .asset_path(&Entry::Folder("characters".to_string()))
+                .unwrap()
+                .to_string(),
+            "remote://textures/characters"
+        );
+        let sources = AssetBrowserLocation::sources_root();
+        assert_eq!(
+            sources
+                .asset_path(&Entry::Source(AssetSourceId::Name("remote".into())))
+                .unwrap()
+                .to_string(),
+            "remote://"
+        );
+        assert_eq!(
+            sources.asset_path(&Entry::File("hero.png".to_string())),
+            None
+        );
+    }
+
+    #[test]
+    fn location_history_moves_between_stacks() {
+        let location = |path: &str| AssetBrowserLocation {
+            source_id: Some(AssetSourceId::Default),
+            path: PathBuf::from(path),
+        };
+        let mut history = LocationHistory::default();
+        assert_eq!(history.go_back(), None);
+        history.visit(&location(""));
+        history.visit(&location("textures"));
+        history.visit(&location("textures"));
+        history.visit(&location("textures/characters"));
+        assert_eq!(history.back, [location(""), location("textures")]);
+
+        assert_eq!(history.go_back(), Some(location("textures")));
+        // The location change caused by going back is already recorded
+        history.visit(&location("textures"));
+        assert_eq!(history.go_back(), Some(location("")));
+        assert_eq!(history.go_back(), None);
+        assert_eq!(
+            history.forward,
+            [location("textures/characters"), location("textures")]
+        );
+
+        assert_eq!(history.go_forward(), Some(location("textures")));
+        assert_eq!(history.back, [location("")]);
+        history.visit(&location("audio"));
+        assert!(
+            history.forward.is_empty(),
+            "visiting a new location clears the forward stack"
+        );
+        assert_eq!(history.back, [location(""), location("textures")]);
+    }
+
+    #[test]
+    fn bookmarks_are_unique_per_location() {
+        let textures = AssetBrowserLocation {
+            source_id: Some(AssetSourceId::Default),
+            path: PathBuf::from("textures/characters"),
+        };
+        let mut bookmarks = BrowserBookmarks::default();
+        bookmarks.add(BrowserBookmarks::default_label(&textures), &textures);
+        bookmarks.add("Sources", &AssetBrowserLocation::sources_root());
+        bookmarks.add("Heroes", &textures);
+        assert_eq!(bookmarks.0.len(), 2);
+        assert_eq!(bookmarks.0[0].0, "Heroes");
+        assert_eq!(bookmarks.location(0), Some(textures.clone()));
+        assert_eq!(
+            BrowserBookmarks::default_label(&AssetBrowserLocation::default()),
+            "Default"
+        );
+        assert_eq!(BrowserBookmarks::default_label(&textures), "characters");
+
+        assert!(bookmarks.remove(&textures));
+        assert!(!bookmarks.remove(&textures));
+        assert!(!bookmarks.contains(&textures));
+        assert!(bookmarks.contains(&AssetBrowserLocation::sources_root()));
+    }
+
+    #[test]
+    fn icon_overrides_match_extensions_case_insensitively() {
+        let level_icon = Handle::<Image>::default();
+        let overrides =
+            PreviewIconOverrides(HashMap::from([("level".to_string(), level_icon.clone())]));
+        assert_eq!(overrides.icon("forest.level"), Some(&level_icon));
+        assert_eq!(overrides.icon("Forest.LEVEL"), Some(&level_icon));
+        assert_eq!(overrides.icon("forest.prefab"), None);
+        assert_eq!(overrides.icon("level"), None);
+    }
+
+    #[test]
+    fn placeholder_icons_are_picked_by_category() {
+        assert_eq!(FileCategory::from_file_name("hit.OGG"), FileCategory::Audio);
+        assert_eq!(
+            FileCategory::from_file_name("scene.ron"),
+            FileCategory::Text
+        );
+        assert_eq!(
+            FileCategory::from_file_name("pack.zip"),
+            FileCategory::Archive
+        );
+        assert_eq!(
+            FileCategory::from_file_name("grass.png"),
+            FileCategory::Image
+        );
+        assert_eq!(
+            FileCategory::from_file_name("forest.level"),
+            FileCategory::Unknown
+        );
+        assert_eq!(
+            FileCategory::from_file_name("Makefile"),
+            FileCategory::Unknown
+        );
+
+        let mut images = Assets::<Image>::default();
+        let audio_icon = images.add(Image::default());
+        let unknown_icon = images.add(Image::default());
+        let placeholders = PlaceholderIcons(HashMap::from([
+            (FileCategory::Audio, audio_icon.clone()),
+            (FileCategory::Unknown, unknown_icon.clone()),
+        ]));
+        assert_eq!(placeholders.icon("hit.wav"), Some(&audio_icon));
+        assert_eq!(placeholders.icon("notes.txt"), Some(&unknown_icon));
+    }
+
+    #[test]
+    fn recent_assets_are_deduplicated_and_capped() {
+        let mut recent_assets = RecentAssets::with_capacity(3);
+        for path in ["a.png", "b.png", "c.png", "a.png", "d.png"] {
+            recent_assets.push(AssetPath::from(path));
         }
+        let recent = |recent_assets: &RecentAssets| {
+            recent_assets
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(recent(&recent_assets), ["d.png", "a.png", "c.png"]);
+
+        recent_assets.push(AssetPath::from("c.png").with_source("thumbnail_cache"));
+        assert_eq!(
+            recent(&recent_assets),
+            ["thumbnail_cache://c.png", "d.png", "a.png"],
+            "the same path in another source is another asset"
+        );
+
+        recent_assets.set_capacity(1);
+        assert_eq!(recent(&recent_assets), ["thumbnail_cache://c.png"]);
+    }
+
+    #[test]
+    fn thumbnail_size_snaps_to_configured_sizes() {
+        assert_eq!(ThumbnailSize(0).clamped(), ThumbnailSize(32));
+        assert_eq!(ThumbnailSize(60).clamped(), ThumbnailSize(64));
+        assert_eq!(ThumbnailSize(1000).clamped(), ThumbnailSize(128));
+
+        assert_eq!(ThumbnailSize::from_slider(-1.0), ThumbnailSize(32));
+        assert_eq!(ThumbnailSize::from_slider(0.5), ThumbnailSize(64));
+        assert_eq!(ThumbnailSize::from_slider(2.0), ThumbnailSize(128));
+        for size in THUMBNAIL_SIZES {
+            let size = ThumbnailSize(size);
+            assert_eq!(ThumbnailSize::from_slider(size.slider_fraction()), size);
+        }
+        assert_eq!(ThumbnailSize::default().tile_outer_size(), 110.0);
+    }
+
+    #[test]
+    fn next_batch_takes_at_most_the_budget_of_missing_visible_tiles() {
+        let budget = TileSpawnBudget(64);
+        // Tiles scrolled into view next to the ones already spawned
+        let visible = 100..1100;
+        let mut spawned: HashSet<usize> = (100..150).collect();
+        let mut frames = 0;
+        loop {
+            let batch = budget.next_batch(visible.clone(), &spawned);
+            if batch.is_empty() {
+                break;
+            }
+            assert!(batch.len() <= 64);
+            assert!(batch.iter().all(|index| visible.contains(index)));
+            assert!(batch.iter().all(|index| !spawned.contains(index)));
+            assert!(batch.is_sorted());
+            spawned.extend(batch);
+            frames += 1;
+        }
+        assert_eq!(spawned.len(), 1000);
+        assert_eq!(frames, 15);
+
+        let first_frame = TileSpawnBudget(0).next_batch(0..10, &HashSet::from([0, 2]));
+        assert_eq!(
+            first_frame,
+            [1],
+            "a budget of 0 still spawns a tile per frame"
+        );
+    }
+
+    #[test]
+    fn sources_root_lists_registered_sources() {
+        use bevy::asset::io::AssetSourceBuilder;
+
+        let mut asset_source_builders = AssetSourceBuilders::default();
+        asset_source_builders.insert(
+            AssetSourceId::Default,
+            AssetSourceBuilder::platform_default("assets", None),
+        );
+        asset_source_builders.insert(
+            AssetSourceId::Name("project".into()),
+            AssetSourceBuilder::platform_default("project", None),
+        );
+        let mut world = World::new();
+        world.insert_resource(asset_source_builders);
+        world.insert_resource(AssetBrowserLocation::sources_root());
+        world.insert_resource(DefaultSourceFilePath(PathBuf::from("assets")));
+        world.init_resource::<ShowHiddenFiles>();
+        world.init_resource::<IgnorePatterns>();
+        world
+            .run_system_cached(io::task::fetch_directory_content)
+            .unwrap();
+
+        let content = &world.resource::<DirectoryContent>().0;
+        assert_eq!(content.len(), 2);
+        assert!(content.contains(&Entry::Source(AssetSourceId::Default)));
+        assert!(content.contains(&Entry::Source(AssetSourceId::Name("project".into()))));
+    }
+
+    /// An app displaying the `textures` directory of a Default source rooted at `root`, without fetching it yet
+    fn test_app(root: &TempDir) -> App {
+        use bevy::{
+            asset::io::AssetSourceBuilder,
+            tasks::{IoTaskPool, TaskPool},
+        };
+
+        IoTaskPool::get_or_init(TaskPool::default);
+        let mut asset_source_builders = AssetSourceBuilders::default();
+        asset_source_builders.insert(
+            AssetSourceId::Default,
+            AssetSourceBuilder::platform_default(root.path().to_str().unwrap(), None),
+        );
+        let mut app = App::new();
+        app.insert_resource(asset_source_builders)
+            .insert_resource(AssetBrowserLocation {
+                source_id: Some(AssetSourceId::Default),
+                path: PathBuf::from("textures"),
+            })
+            .insert_resource(DefaultSourceFilePath(root.path().to_path_buf()))
+            .init_resource::<DirectoryContent>()
+            .init_resource::<DirectoryEntryDetails>()
+            .init_resource::<io::task::StreamedEntries>()
+            .init_resource::<DirectoryContentOrder>()
+            .init_resource::<ShowHiddenFiles>()
+            .init_resource::<IgnorePatterns>()
+            .add_event::<NavigateAssetBrowser>()
+            .add_systems(
+                Update,
+                (
+                    navigate_asset_browser.run_if(on_event::<NavigateAssetBrowser>),
+                    io::task::poll_task.run_if(io::task::fetch_task_is_running),
+                ),
+            );
+        app
+    }
+
+    /// Update the app until the running fetch is done, and take the [`DirectoryAccess`] it inserted
+    fn wait_for_fetch(app: &mut App) -> Option<DirectoryAccess> {
+        for _ in 0..500 {
+            app.update();
+            if let Some(access) = app.world_mut().remove_resource::<DirectoryAccess>() {
+                return Some(access);
+            }
+            std::thread::sleep(std::time::Duration::from_millis(10));
+        }
+        None
+    }
+
+    /// Fetch the content of the current location, see [`wait_for_fetch`]
+    fn fetch(app: &mut App) -> Option<DirectoryAccess> {
+        app.world_mut()
+            .run_system_cached(io::task::fetch_directory_content)
+            .unwrap();
+        wait_for_fetch(app)
+    }
+
+    #[test]
+    fn missing_directory_reports_fetch_error() {
+        let root = TempDir::new("fetch_error");
+        let mut app = test_app(&root);
+
+        assert_eq!(fetch(&mut app), Some(DirectoryAccess::Granted));
+        let fetch_error = app.world().get_resource::<DirectoryFetchError>().cloned();
+        assert_eq!(
+            fetch_error.map(|fetch_error| fetch_error.path),
+            Some(PathBuf::from("textures"))
+        );
+
+        std::fs::create_dir_all(root.join("textures")).unwrap();
+        assert_eq!(fetch(&mut app), Some(DirectoryAccess::Granted));
+        assert!(
+            !app.world().contains_resource::<DirectoryFetchError>(),
+            "a successful fetch clears the previous error"
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn unreadable_directory_displays_access_denied() {
+        use std::os::unix::fs::PermissionsExt;
+
+        use bevy_editor_styles::Theme;
+
+        let root = TempDir::new("access_denied");
+        let textures = root.join("textures");
+        std::fs::create_dir_all(&textures).unwrap();
+        std::fs::set_permissions(&textures, std::fs::Permissions::from_mode(0o000)).unwrap();
+        if std::fs::read_dir(&textures).is_ok() {
+            // Running as root, the permissions aren't enforced
+            std::fs::set_permissions(&textures, std::fs::Permissions::from_mode(0o755)).unwrap();
+            return;
+        }
+        let mut app = test_app(&root);
+        app.add_plugins(AssetPlugin::default())
+            .init_resource::<Theme>()
+            .init_resource::<BrowserViewMode>()
+            .init_resource::<CaseCollisions>()
+            .init_resource::<DirectoryFilter>()
+            .init_resource::<DirectoryTypeFilter>()
+            .init_resource::<SelectedEntry>();
+
+        let access = fetch(&mut app);
+        // Readable again so the TempDir can be removed
+        std::fs::set_permissions(&textures, std::fs::Permissions::from_mode(0o755)).unwrap();
+        assert_eq!(access, Some(DirectoryAccess::Denied));
+
+        app.insert_resource(DirectoryAccess::Denied);
+        let scroll_box = app.world_mut().spawn_empty().id();
+        app.world_mut().spawn((
+            ui::directory_content::AssetBrowserContent,
+            ChildOf(scroll_box),
+        ));
+        app.world_mut()
+            .run_system_cached(ui::directory_content::refresh_ui)
+            .unwrap();
+        let messages = app
+            .world_mut()
+            .query::<&Text>()
+            .iter(app.world())
+            .map(|text| text.0.clone())
+            .collect::<Vec<_>>();
+        assert_eq!(messages, vec!["Access denied: textures".to_string()]);
+    }
+
+    #[test]
+    fn streamed_entries_are_sorted() {
+        let root = TempDir::new("streamed_entries");
+        std::fs::create_dir_all(root.join("textures").join("characters")).unwrap();
+        for file_name in ["b.png", "a.png", "c.png"] {
+            std::fs::write(root.join("textures").join(file_name), b"").unwrap();
+        }
+        let mut app = test_app(&root);
+
+        assert_eq!(fetch(&mut app), Some(DirectoryAccess::Granted));
+        let content = app.world().resource::<DirectoryContent>().clone();
+        let mut sorted = content.clone();
+        let details = app.world().resource::<DirectoryEntryDetails>();
+        DirectoryContentOrder::default().sort(&mut sorted, details, None);
+        assert_eq!(content.0.len(), 4);
+        assert_eq!(
+            content, sorted,
+            "the streamed entries are sorted as they arrive"
+        );
+    }
+
+    #[test]
+    fn entry_details_are_read_from_disk() {
+        let root = TempDir::new("entry_details");
+        std::fs::create_dir_all(root.join("textures").join("characters")).unwrap();
+        std::fs::write(root.join("textures").join("player.png"), b"player").unwrap();
+        let mut app = test_app(&root);
+
+        assert_eq!(fetch(&mut app), Some(DirectoryAccess::Granted));
+        let details = app.world().resource::<DirectoryEntryDetails>();
+        let file = details.get(&Entry::File("player.png".to_string())).unwrap();
+        assert_eq!(file.size, Some(6));
+        assert!(file.modified.is_some());
+        let folder = details
+            .get(&Entry::Folder("characters".to_string()))
+            .unwrap();
+        assert_eq!(folder.size, None, "folders have no size");
+    }
+
+    #[test]
+    fn stale_fetch_results_are_discarded() {
+        let root = TempDir::new("stale_fetch");
+        std::fs::create_dir_all(root.join("textures")).unwrap();
+        std::fs::write(root.join("textures").join("player.png"), b"player").unwrap();
+        let mut app = test_app(&root);
+
+        app.world_mut()
+            .run_system_cached(io::task::fetch_directory_content)
+            .unwrap();
+        // Navigate away before the fetch is done
+        app.world_mut().resource_mut::<AssetBrowserLocation>().path = PathBuf::from("sounds");
+        for _ in 0..500 {
+            app.update();
+            if !app
+                .world_mut()
+                .run_system_cached(io::task::fetch_task_is_running)
+                .unwrap()
+            {
+                break;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(10));
+        }
+
+        assert!(
+            app.world().resource::<DirectoryContent>().0.is_empty(),
+            "the content of the previous location shouldn't be displayed"
+        );
+        assert!(!app.world().contains_resource::<DirectoryAccess>());
+    }
+
+    #[test]
+    fn navigate_event_updates_location_and_fetches() {
+        let root = TempDir::new("navigate_event");
+        let mut app = test_app(&root);
+        let initial_location = app.world().resource::<AssetBrowserLocation>().clone();
+
+        app.world_mut().send_event(NavigateAssetBrowser {
+            source_id: Some(AssetSourceId::Default),
+            path: PathBuf::from("../outside"),
+        });
+        app.update();
+        assert_eq!(
+            *app.world().resource::<AssetBrowserLocation>(),
+            initial_location
+        );
+
+        app.world_mut().send_event(NavigateAssetBrowser {
+            source_id: Some(AssetSourceId::Default),
+            path: PathBuf::from("textures/characters"),
+        });
+        assert_eq!(wait_for_fetch(&mut app), Some(DirectoryAccess::Granted));
+        assert_eq!(
+            *app.world().resource::<AssetBrowserLocation>(),
+            AssetBrowserLocation {
+                source_id: Some(AssetSourceId::Default),
+                path: PathBuf::from("textures/characters"),
+            }
+        );
+        // The directory doesn't exist, the error shows the new location was fetched
+        let fetch_error = app.world().get_resource::<DirectoryFetchError>().cloned();
+        assert_eq!(
+            fetch_error.map(|fetch_error| fetch_error.path),
+            Some(PathBuf::from("textures/characters"))
+        );
     }
 }
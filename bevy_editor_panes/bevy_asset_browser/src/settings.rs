@@ -0,0 +1,217 @@
+//! Export and import the asset browser settings, so a team can share a consistent configuration
+
+use std::{fs::File, io, path::PathBuf};
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    BrowserBookmarks, BrowserViewMode, CheckerboardBackground, DirectoryContentOrder,
+    DirectoryTypeFilter, ExtensionBadges, IgnorePatterns, ShowHiddenFiles, ThumbnailSize,
+};
+
+/// The name of the settings file, looked up at the root of the project
+pub const SETTINGS_FILE: &str = "asset_browser.ron";
+
+/// Path of the settings file applied on startup, if it exists
+#[derive(Resource, Debug, Clone, PartialEq, Eq)]
+pub struct AssetBrowserSettingsPath(pub PathBuf);
+
+/// All the persistent settings of the asset browser
+///
+/// Missing fields fall back to their default value, so older settings files keep loading.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AssetBrowserSettings {
+    /// See [`DirectoryContentOrder`]
+    pub order: DirectoryContentOrder,
+    /// See [`DirectoryTypeFilter`]
+    pub type_filter: DirectoryTypeFilter,
+    /// See [`CheckerboardBackground`]
+    pub checkerboard_background: CheckerboardBackground,
+    /// See [`ExtensionBadges`]
+    pub extension_badges: ExtensionBadges,
+    /// See [`ThumbnailSize`]
+    pub thumbnail_size: ThumbnailSize,
+    /// See [`BrowserViewMode`]
+    pub view_mode: BrowserViewMode,
+    /// See [`ShowHiddenFiles`]
+    pub show_hidden_files: ShowHiddenFiles,
+    /// See [`IgnorePatterns`]
+    pub ignore_patterns: IgnorePatterns,
+    /// See [`BrowserBookmarks`]
+    pub bookmarks: BrowserBookmarks,
+}
+
+impl AssetBrowserSettings {
+    /// Collect the settings currently used by the asset browser
+    pub fn from_world(world: &World) -> Self {
+        Self {
+            order: world
+                .get_resource::<DirectoryContentOrder>()
+                .cloned()
+                .unwrap_or_default(),
+            type_filter: world
+                .get_resource::<DirectoryTypeFilter>()
+                .cloned()
+                .unwrap_or_default(),
+            checkerboard_background: world
+                .get_resource::<CheckerboardBackground>()
+                .copied()
+                .unwrap_or_default(),
+            extension_badges: world
+                .get_resource::<ExtensionBadges>()
+                .copied()
+                .unwrap_or_default(),
+            thumbnail_size: world
+                .get_resource::<ThumbnailSize>()
+                .copied()
+                .unwrap_or_default(),
+            view_mode: world
+                .get_resource::<BrowserViewMode>()
+                .copied()
+                .unwrap_or_default(),
+            show_hidden_files: world
+                .get_resource::<ShowHiddenFiles>()
+                .copied()
+                .unwrap_or_default(),
+            ignore_patterns: world
+                .get_resource::<IgnorePatterns>()
+                .cloned()
+                .unwrap_or_default(),
+            bookmarks: world
+                .get_resource::<BrowserBookmarks>()
+                .cloned()
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Insert the settings into their corresponding resources
+    pub fn apply(self, world: &mut World) {
+        world.insert_resource(self.order);
+        world.insert_resource(self.type_filter);
+        world.insert_resource(self.checkerboard_background);
+        world.insert_resource(self.extension_badges);
+        // Hand-edited files may contain a size the slider can't pick
+        world.insert_resource(self.thumbnail_size.clamped());
+        world.insert_resource(self.view_mode);
+        world.insert_resource(self.show_hidden_files);
+        world.insert_resource(self.ignore_patterns);
+        world.insert_resource(self.bookmarks);
+    }
+
+    /// Load the settings from a RON file
+    pub fn load(path: impl Into<PathBuf>) -> io::Result<Self> {
+        let file = File::open(path.into())?;
+        ron::de::from_reader(file).map_err(|error| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("Couldn't parse asset browser settings file: {error}"),
+            )
+        })
+    }
+
+    /// Save the settings to a RON file
+    pub fn save(&self, path: impl Into<PathBuf>) -> io::Result<()> {
+        let file = File::create(path.into())?;
+        ron::Options::default()
+            .to_io_writer_pretty(file, self, ron::ser::PrettyConfig::default())
+            .map_err(|error| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("Failed to serialize asset browser settings: {error}"),
+                )
+            })
+    }
+}
+
+/// Apply the settings file pointed by [`AssetBrowserSettingsPath`] if it exists
+pub(crate) fn load_settings(world: &mut World) {
+    let Some(path) = world
+        .get_resource::<AssetBrowserSettingsPath>()
+        .map(|path| path.0.clone())
+    else {
+        return;
+    };
+    if !path.exists() {
+        return;
+    }
+    match AssetBrowserSettings::load(&path) {
+        Ok(settings) => settings.apply(world),
+        Err(e) => eprintln!("Failed to load {}: {e}", path.display()),
+    }
+}
+
+/// Save the current settings to the file pointed by [`AssetBrowserSettingsPath`]
+pub fn save_settings(world: &mut World) {
+    let Some(path) = world
+        .get_resource::<AssetBrowserSettingsPath>()
+        .map(|path| path.0.clone())
+    else {
+        return;
+    };
+    if let Err(e) = AssetBrowserSettings::from_world(world).save(&path) {
+        eprintln!("Failed to save {}: {e}", path.display());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::TempDir;
+
+    #[test]
+    fn settings_round_trip_and_apply() {
+        let settings = AssetBrowserSettings {
+            order: DirectoryContentOrder::ReverseAlphabetical,
+            type_filter: DirectoryTypeFilter {
+                extensions: vec!["png".to_string(), "gltf".to_string()],
+                enabled: true,
+            },
+            checkerboard_background: CheckerboardBackground(true),
+            extension_badges: ExtensionBadges(true),
+            thumbnail_size: ThumbnailSize(96),
+            view_mode: BrowserViewMode::Details,
+            show_hidden_files: ShowHiddenFiles(true),
+            ignore_patterns: IgnorePatterns(vec!["*.tmp".to_string()]),
+            bookmarks: BrowserBookmarks(vec![
+                (
+                    "Characters".to_string(),
+                    Some(bevy::asset::io::AssetSourceId::Default),
+                    PathBuf::from("textures/characters"),
+                ),
+                (
+                    "Cache".to_string(),
+                    Some(bevy::asset::io::AssetSourceId::from("thumbnail_cache")),
+                    PathBuf::new(),
+                ),
+                ("Sources".to_string(), None, PathBuf::new()),
+            ]),
+        };
+        let root = TempDir::new("settings");
+        let path = root.join(SETTINGS_FILE);
+        settings.save(&path).unwrap();
+        let loaded = AssetBrowserSettings::load(&path).unwrap();
+        assert_eq!(loaded, settings);
+
+        let mut world = World::new();
+        world.insert_resource(DirectoryContentOrder::Alphabetical);
+        loaded.apply(&mut world);
+        assert_eq!(
+            AssetBrowserSettings::from_world(&world),
+            settings,
+            "applying the settings should set the corresponding resources"
+        );
+        assert_eq!(
+            *world.resource::<DirectoryContentOrder>(),
+            DirectoryContentOrder::ReverseAlphabetical
+        );
+    }
+
+    #[test]
+    fn missing_fields_use_defaults() {
+        let settings: AssetBrowserSettings = ron::from_str("(order: ReverseAlphabetical)").unwrap();
+        assert_eq!(settings.order, DirectoryContentOrder::ReverseAlphabetical);
+        assert_eq!(settings.type_filter, DirectoryTypeFilter::default());
+    }
+}
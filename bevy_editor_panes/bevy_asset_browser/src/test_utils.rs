@@ -0,0 +1,35 @@
+//! Helpers shared by the tests of the crate
+
+use std::path::{Path, PathBuf};
+
+/// An empty directory in the OS temp directory, removed with all its content once dropped
+///
+/// The directory is removed even if the test panics, so a failed assert doesn't leave files behind.
+pub(crate) struct TempDir(PathBuf);
+
+impl TempDir {
+    /// Create the directory, `name` must be unique among the tests so they can run in parallel
+    pub(crate) fn new(name: &str) -> Self {
+        let path =
+            std::env::temp_dir().join(format!("bevy_asset_browser_{name}_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&path);
+        std::fs::create_dir_all(&path).unwrap();
+        Self(path)
+    }
+
+    /// Path of the directory
+    pub(crate) fn path(&self) -> &Path {
+        &self.0
+    }
+
+    /// Path of an entry inside the directory
+    pub(crate) fn join(&self, path: impl AsRef<Path>) -> PathBuf {
+        self.0.join(path)
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
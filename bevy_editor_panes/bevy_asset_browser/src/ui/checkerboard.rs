@@ -0,0 +1,88 @@
+//! Checkerboard displayed behind images so their transparency is visible, see [`CheckerboardBackground`]
+
+use bevy::{
+    asset::RenderAssetUsages,
+    ecs::system::SystemParam,
+    image::ImageSampler,
+    prelude::*,
+    render::render_resource::{Extent3d, TextureDimension, TextureFormat},
+};
+
+use crate::CheckerboardBackground;
+
+/// Size in pixels of one square of the checkerboard
+const CHECKERBOARD_SQUARE_SIZE: u32 = 6;
+
+/// Colors of the checkerboard squares
+const CHECKERBOARD_COLORS: [[u8; 4]; 2] = [[0x50, 0x50, 0x54, 0xFF], [0x38, 0x38, 0x3C, 0xFF]];
+
+/// The tileable checkerboard texture
+#[derive(Resource, Clone)]
+pub struct CheckerboardImage(pub Handle<Image>);
+
+/// Marker of the nodes displaying a checkerboard behind their children
+#[derive(Component)]
+pub struct Checkerboard;
+
+/// The checkerboard to display behind images, if [`CheckerboardBackground`] is enabled
+#[derive(SystemParam)]
+pub(crate) struct TileBackground<'w> {
+    enabled: Res<'w, CheckerboardBackground>,
+    image: Option<Res<'w, CheckerboardImage>>,
+}
+
+impl TileBackground<'_> {
+    /// Get the checkerboard to display behind images, if enabled
+    pub(crate) fn checkerboard(&self) -> Option<&CheckerboardImage> {
+        self.image.as_deref().filter(|_| self.enabled.0)
+    }
+}
+
+/// Create the [`CheckerboardImage`], made of two squares per side so it can be tiled
+pub(crate) fn create_checkerboard_image(mut commands: Commands, mut images: ResMut<Assets<Image>>) {
+    let size = CHECKERBOARD_SQUARE_SIZE * 2;
+    let data = (0..size * size)
+        .flat_map(|pixel| {
+            let (x, y) = (pixel % size, pixel / size);
+            CHECKERBOARD_COLORS
+                [((x / CHECKERBOARD_SQUARE_SIZE + y / CHECKERBOARD_SQUARE_SIZE) % 2) as usize]
+        })
+        .collect();
+    let mut image = Image::new(
+        Extent3d {
+            width: size,
+            height: size,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        data,
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::RENDER_WORLD,
+    );
+    image.sampler = ImageSampler::nearest();
+    commands.insert_resource(CheckerboardImage(images.add(image)));
+}
+
+/// Spawn a checkerboard node sized by its children, add the image to display in front of it as a child
+pub(crate) fn spawn_checkerboard<'a>(
+    commands: &'a mut Commands,
+    checkerboard: &CheckerboardImage,
+) -> EntityCommands<'a> {
+    let root = commands.spawn((Checkerboard, Node::default())).id();
+    // Spawned first so it stays behind the image
+    commands.spawn((
+        ImageNode::new(checkerboard.0.clone()).with_mode(NodeImageMode::Tiled {
+            tile_x: true,
+            tile_y: true,
+            stretch_value: 1.0,
+        }),
+        Node {
+            position_type: PositionType::Absolute,
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            ..default()
+        },
+        ChildOf(root),
+    ));
+    commands.entity(root)
+}
@@ -0,0 +1,138 @@
+//! Confirmation asked before permanently deleting entries
+
+use std::path::PathBuf;
+
+use bevy::prelude::*;
+use bevy_editor_styles::Theme;
+
+use super::{directory_content::delete_paths, top_bar::spawn_top_bar_button};
+
+/// Node covering the whole window while the confirmation is displayed, so nothing else can be picked
+#[derive(Component)]
+pub struct DeleteConfirmation;
+
+/// Check if deleted entries are gone for good, they are only moved to the OS trash with the `trash` feature
+pub(crate) fn deletion_is_permanent() -> bool {
+    !cfg!(feature = "trash")
+}
+
+/// Check if deleting `count` entries from their tiles has to be confirmed first
+///
+/// A single entry is still deleted right away, like in a file manager.
+pub(crate) fn deletion_needs_confirmation(count: usize) -> bool {
+    count > 1 && deletion_is_permanent()
+}
+
+/// Ask before deleting the entries at `paths`, relative to the Default source root
+///
+/// Pressing outside of the confirmation cancels it.
+pub(crate) fn spawn_delete_confirmation(
+    commands: &mut Commands,
+    theme: &Theme,
+    paths: Vec<PathBuf>,
+) {
+    let root = commands
+        .spawn((
+            DeleteConfirmation,
+            Node {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            BackgroundColor(Color::BLACK.with_alpha(0.4)),
+            GlobalZIndex(i32::MAX - 2),
+        ))
+        .observe(|trigger: On<Pointer<Press>>, mut commands: Commands| {
+            commands.entity(trigger.target()).despawn();
+        })
+        .id();
+    let panel = commands
+        .spawn((
+            Node {
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::End,
+                row_gap: Val::Px(10.0),
+                padding: UiRect::all(Val::Px(10.0)),
+                ..default()
+            },
+            BoxShadow::from(ShadowStyle {
+                blur_radius: Val::Px(3.0),
+                x_offset: Val::ZERO,
+                y_offset: Val::ZERO,
+                color: Color::BLACK.with_alpha(0.8),
+                ..default()
+            }),
+            theme.context_menu.background_color,
+            theme.general.border_radius,
+            ChildOf(root),
+        ))
+        .observe(|mut trigger: On<Pointer<Press>>| {
+            // Prevent the root from cancelling the confirmation when pressing inside of it
+            trigger.propagate(false);
+        })
+        .id();
+    commands.spawn((
+        Text::new(match paths.as_slice() {
+            [path] => format!(
+                "Permanently delete {}? It can't be restored.",
+                path.display()
+            ),
+            paths => format!(
+                "Permanently delete {} entries? They can't be restored.",
+                paths.len()
+            ),
+        }),
+        TextFont {
+            font: theme.text.font.clone(),
+            font_size: 12.0,
+            ..default()
+        },
+        TextColor(theme.text.text_color),
+        ChildOf(panel),
+    ));
+    let buttons = commands
+        .spawn((
+            Node {
+                flex_direction: FlexDirection::Row,
+                ..default()
+            },
+            ChildOf(panel),
+        ))
+        .id();
+    spawn_top_bar_button(commands, theme, "Cancel")
+        .insert(ChildOf(buttons))
+        .observe(
+            move |trigger: On<Pointer<Release>>, mut commands: Commands| {
+                if trigger.event().button != PointerButton::Primary {
+                    return;
+                }
+                commands.entity(root).despawn();
+            },
+        );
+    spawn_top_bar_button(commands, theme, "Delete")
+        .insert(ChildOf(buttons))
+        .observe(
+            move |trigger: On<Pointer<Release>>, mut commands: Commands| {
+                if trigger.event().button != PointerButton::Primary {
+                    return;
+                }
+                commands.entity(root).despawn();
+                commands.run_system_cached_with(delete_paths, paths.clone());
+            },
+        );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_deleting_several_entries_permanently_is_confirmed() {
+        assert!(!deletion_needs_confirmation(0));
+        assert!(!deletion_needs_confirmation(1));
+        assert_eq!(deletion_needs_confirmation(2), !cfg!(feature = "trash"));
+    }
+}
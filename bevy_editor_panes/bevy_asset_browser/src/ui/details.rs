@@ -0,0 +1,380 @@
+//! Columns of the [`BrowserViewMode::Details`](crate::BrowserViewMode::Details) view, displaying the type,
+//! size and modification date of the entries, see [`EntryDetails`]
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use bevy::{feathers::cursor::EntityCursor, prelude::*, window::SystemCursorIcon};
+use bevy_editor_styles::Theme;
+
+use crate::{DETAILS_ROW_HEIGHT, DirectoryContentOrder, Entry, EntryDetails};
+
+use super::nodes::extension_badge;
+
+/// Height in pixels of the icons at the start of the rows
+pub(crate) const DETAILS_ICON_SIZE: f32 = 16.0;
+
+/// A column of the details view, clicking its header sorts the entries by it
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DetailsColumn {
+    /// Name of the entry, displayed next to its icon
+    Name,
+    /// Extension of the files, or the kind of entry for folders and sources
+    Type,
+    /// Size of the files
+    Size,
+    /// Last modification date of the folders and files
+    Modified,
+}
+
+impl DetailsColumn {
+    /// The columns following the name, in display order
+    const DETAIL_COLUMNS: [Self; 3] = [Self::Type, Self::Size, Self::Modified];
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::Name => "Name",
+            Self::Type => "Type",
+            Self::Size => "Size",
+            Self::Modified => "Modified",
+        }
+    }
+
+    /// Width in pixels of the column, `None` for the name column taking the remaining space
+    fn width(self) -> Option<f32> {
+        match self {
+            Self::Name => None,
+            Self::Type => Some(50.0),
+            Self::Size => Some(70.0),
+            Self::Modified => Some(110.0),
+        }
+    }
+
+    /// The orders sorting the entries by this column, ascending then descending
+    fn orders(self) -> [DirectoryContentOrder; 2] {
+        match self {
+            Self::Name => [
+                DirectoryContentOrder::Alphabetical,
+                DirectoryContentOrder::ReverseAlphabetical,
+            ],
+            Self::Type => [
+                DirectoryContentOrder::Type,
+                DirectoryContentOrder::ReverseType,
+            ],
+            Self::Size => [
+                DirectoryContentOrder::Size,
+                DirectoryContentOrder::ReverseSize,
+            ],
+            Self::Modified => [
+                DirectoryContentOrder::Modified,
+                DirectoryContentOrder::ReverseModified,
+            ],
+        }
+    }
+
+    /// The order picked by clicking the header: ascending, or flipping the direction if already sorted by this column
+    pub fn next_order(self, current: &DirectoryContentOrder) -> DirectoryContentOrder {
+        let [ascending, descending] = self.orders();
+        if *current == ascending {
+            descending
+        } else {
+            ascending
+        }
+    }
+
+    /// Label of the header, with an arrow if the entries are sorted by this column
+    fn header_label(self, current: &DirectoryContentOrder) -> String {
+        let [ascending, descending] = self.orders();
+        if *current == ascending {
+            format!("{} \u{25B2}", self.label())
+        } else if *current == descending {
+            format!("{} \u{25BC}", self.label())
+        } else {
+            self.label().to_string()
+        }
+    }
+
+    /// Text displayed in this column for an entry, empty if unknown
+    fn value(self, entry: &Entry, details: Option<&EntryDetails>) -> String {
+        match self {
+            Self::Name => match entry {
+                Entry::Folder(name) | Entry::File(name) => name.clone(),
+                Entry::Source(_) => String::new(),
+            },
+            Self::Type => match entry {
+                Entry::Source(_) => "Source".to_string(),
+                Entry::Folder(_) => "Folder".to_string(),
+                Entry::File(name) => extension_badge(name).unwrap_or_else(|| "File".to_string()),
+            },
+            Self::Size => details
+                .and_then(|details| details.size)
+                .map(format_size)
+                .unwrap_or_default(),
+            Self::Modified => details
+                .and_then(|details| details.modified)
+                .map(format_modified)
+                .unwrap_or_default(),
+        }
+    }
+}
+
+/// Format a size in bytes with binary units, e.g. `1.5 KB`
+pub(crate) fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KB", "MB", "GB", "TB"];
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{size:.1} {}", UNITS[unit])
+}
+
+/// Format a date as `YYYY-MM-DD HH:MM` in UTC, dates before 1970 aren't displayed
+pub(crate) fn format_modified(modified: SystemTime) -> String {
+    let Ok(since_epoch) = modified.duration_since(UNIX_EPOCH) else {
+        return String::new();
+    };
+    let seconds = since_epoch.as_secs();
+    let (year, month, day) = civil_from_days((seconds / 86_400) as i64);
+    let minutes = seconds % 86_400 / 60;
+    format!(
+        "{year:04}-{month:02}-{day:02} {:02}:{:02}",
+        minutes / 60,
+        minutes % 60
+    )
+}
+
+/// Convert a number of days since 1970-01-01 to a (year, month, day) date of the proleptic Gregorian calendar
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    // Count from 0000-03-01, so leap days end the 400 years eras and the years
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_from_march = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_from_march + 2) / 5 + 1;
+    let month = if month_from_march < 10 {
+        month_from_march + 3
+    } else {
+        month_from_march - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    (year, month as u32, day as u32)
+}
+
+/// The node of a details row, laying out the icon, the name and the [`DetailsColumn`]s in a line
+fn row_node() -> Node {
+    Node {
+        width: Val::Percent(100.0),
+        height: Val::Px(DETAILS_ROW_HEIGHT - 2.0),
+        margin: UiRect::vertical(Val::Px(1.0)),
+        padding: UiRect::horizontal(Val::Px(5.0)),
+        flex_direction: FlexDirection::Row,
+        align_items: AlignItems::Center,
+        column_gap: Val::Px(5.0),
+        border: UiRect::all(Val::Px(1.0)),
+        ..default()
+    }
+}
+
+/// The node holding the [`DetailsColumn`]s after the name, aligned on the right of the row
+fn detail_columns_node() -> Node {
+    Node {
+        margin: UiRect::left(Val::Auto),
+        flex_direction: FlexDirection::Row,
+        align_items: AlignItems::Center,
+        column_gap: Val::Px(5.0),
+        ..default()
+    }
+}
+
+/// The node of a cell of a [`DetailsColumn`], clipping values longer than the column
+fn cell_node(column: DetailsColumn) -> Node {
+    Node {
+        width: column.width().map_or(Val::Auto, Val::Px),
+        flex_shrink: 0.0,
+        overflow: Overflow::clip(),
+        ..default()
+    }
+}
+
+/// Lay an entry tile out as a row, and add the cells of the columns after its icon and name
+pub(crate) fn spawn_detail_cells(
+    commands: &mut Commands,
+    row: Entity,
+    entry: &Entry,
+    details: Option<&EntryDetails>,
+    theme: &Theme,
+) {
+    commands.entity(row).insert(row_node());
+    let columns = commands
+        .spawn((detail_columns_node(), Pickable::IGNORE, ChildOf(row)))
+        .id();
+    for column in DetailsColumn::DETAIL_COLUMNS {
+        let cell = commands
+            .spawn((cell_node(column), Pickable::IGNORE, ChildOf(columns)))
+            .id();
+        commands.spawn((
+            Text::new(column.value(entry, details)),
+            TextFont {
+                font: theme.text.font.clone(),
+                font_size: 10.0,
+                ..default()
+            },
+            TextColor(theme.text.low_priority),
+            Pickable::IGNORE,
+            ChildOf(cell),
+        ));
+    }
+}
+
+/// Spawn the header row of the details view, clicking a column sorts the entries by it
+pub(crate) fn spawn_details_header<'a>(
+    commands: &'a mut Commands,
+    theme: &Theme,
+    content_order: &DirectoryContentOrder,
+) -> EntityCommands<'a> {
+    let header = commands
+        .spawn(Node {
+            // Start the name column after the icon of the rows
+            padding: UiRect::left(Val::Px(10.0 + DETAILS_ICON_SIZE)),
+            ..row_node()
+        })
+        .id();
+    spawn_header_cell(commands, theme, DetailsColumn::Name, content_order).insert(ChildOf(header));
+    let columns = commands
+        .spawn((detail_columns_node(), ChildOf(header)))
+        .id();
+    for column in DetailsColumn::DETAIL_COLUMNS {
+        spawn_header_cell(commands, theme, column, content_order)
+            .insert((cell_node(column), ChildOf(columns)));
+    }
+    commands.entity(header)
+}
+
+/// Spawn the clickable header of a [`DetailsColumn`]
+fn spawn_header_cell<'a>(
+    commands: &'a mut Commands,
+    theme: &Theme,
+    column: DetailsColumn,
+    content_order: &DirectoryContentOrder,
+) -> EntityCommands<'a> {
+    let mut header_cell = commands.spawn((
+        column,
+        Button,
+        Node::default(),
+        EntityCursor::System(SystemCursorIcon::Pointer),
+    ));
+    header_cell.with_child((
+        Text::new(column.header_label(content_order)),
+        TextFont {
+            font: theme.text.font.clone(),
+            font_size: 10.0,
+            ..default()
+        },
+        TextColor(theme.text.text_color),
+        Pickable::IGNORE,
+    ));
+    header_cell.observe(sort_by_column);
+    header_cell
+}
+
+/// Sort the entries by the clicked [`DetailsColumn`], flipping the direction if they already are
+fn sort_by_column(
+    trigger: On<Pointer<Release>>,
+    column_query: Query<&DetailsColumn>,
+    mut content_order: ResMut<DirectoryContentOrder>,
+) {
+    if trigger.event().button != PointerButton::Primary {
+        return;
+    }
+    let Ok(column) = column_query.get(trigger.target()) else {
+        return;
+    };
+    let order = column.next_order(&content_order);
+    content_order.set_if_neq(order);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[test]
+    fn clicking_a_header_toggles_its_direction() {
+        let mut order = DirectoryContentOrder::Natural;
+        let mut click = |column: DetailsColumn| {
+            order = column.next_order(&order);
+            order.clone()
+        };
+        assert_eq!(click(DetailsColumn::Size), DirectoryContentOrder::Size);
+        assert_eq!(
+            click(DetailsColumn::Size),
+            DirectoryContentOrder::ReverseSize
+        );
+        assert_eq!(click(DetailsColumn::Size), DirectoryContentOrder::Size);
+        assert_eq!(
+            click(DetailsColumn::Modified),
+            DirectoryContentOrder::Modified
+        );
+        assert_eq!(
+            click(DetailsColumn::Name),
+            DirectoryContentOrder::Alphabetical
+        );
+
+        assert_eq!(
+            DetailsColumn::Name.header_label(&DirectoryContentOrder::ReverseAlphabetical),
+            "Name \u{25BC}"
+        );
+        assert_eq!(
+            DetailsColumn::Type.header_label(&DirectoryContentOrder::Type),
+            "Type \u{25B2}"
+        );
+        assert_eq!(
+            DetailsColumn::Size.header_label(&DirectoryContentOrder::Type),
+            "Size"
+        );
+    }
+
+    #[test]
+    fn detail_values_are_human_readable() {
+        assert_eq!(format_size(0), "0 B");
+        assert_eq!(format_size(1023), "1023 B");
+        assert_eq!(format_size(1536), "1.5 KB");
+        assert_eq!(format_size(5 * 1024 * 1024), "5.0 MB");
+        assert_eq!(format_size(u64::MAX), "16777216.0 TB");
+
+        assert_eq!(format_modified(UNIX_EPOCH), "1970-01-01 00:00");
+        assert_eq!(
+            format_modified(UNIX_EPOCH + Duration::from_secs(951_782_400)),
+            "2000-02-29 00:00"
+        );
+        assert_eq!(
+            format_modified(UNIX_EPOCH + Duration::from_secs(1_700_000_000)),
+            "2023-11-14 22:13"
+        );
+        assert_eq!(format_modified(UNIX_EPOCH - Duration::from_secs(1)), "");
+
+        let details = EntryDetails {
+            size: Some(2048),
+            modified: None,
+        };
+        let file = Entry::File("player.png".to_string());
+        assert_eq!(DetailsColumn::Type.value(&file, Some(&details)), "PNG");
+        assert_eq!(DetailsColumn::Size.value(&file, Some(&details)), "2.0 KB");
+        assert_eq!(DetailsColumn::Modified.value(&file, Some(&details)), "");
+        let folder = Entry::Folder("textures".to_string());
+        assert_eq!(DetailsColumn::Type.value(&folder, None), "Folder");
+        assert_eq!(DetailsColumn::Size.value(&folder, None), "");
+        assert_eq!(
+            DetailsColumn::Type.value(&Entry::File("Makefile".to_string()), None),
+            "File"
+        );
+    }
+}
@@ -1,23 +1,153 @@
-use bevy::{asset::io::AssetSourceId, prelude::*};
+use std::{
+    collections::{HashMap, HashSet},
+    ops::Range,
+    path::PathBuf,
+};
+
+use bevy::{asset::io::AssetSourceId, ecs::system::SystemParam, prelude::*, ui::UiGlobalTransform};
+#[cfg(feature = "clipboard")]
+use bevy_clipboard::BevyClipboard;
 use bevy_context_menu::{ContextMenu, ContextMenuOption};
 use bevy_editor_styles::Theme;
-use bevy_scroll_box::{ScrollBox, ScrollBoxContent, spawn_scroll_box};
+use bevy_scroll_box::{ScrollBox, spawn_scroll_box};
+
+use crate::{
+    AssetBrowserLocation, BrowserBookmarks, BrowserViewMode, CaseCollisions, DefaultSourceFilePath,
+    DirectoryAccess, DirectoryContent, DirectoryContentOrder, DirectoryEntryDetails,
+    DirectoryFetchError, DirectoryFilter, DirectoryTypeFilter, DuplicateGroups, Entry,
+    EntryDetails, ExtensionBadges, PlaceholderIcons, PreviewIconOverrides, SelectedEntry,
+    Selection, ThumbnailSize, TileSpawnBudget, io,
+};
+
+use crate::ui::{
+    checkerboard::{CheckerboardImage, TileBackground},
+    delete_confirmation::{deletion_needs_confirmation, spawn_delete_confirmation},
+    details::{spawn_detail_cells, spawn_details_header},
+    drag::{self, DraggableAsset},
+    duplicates,
+    keyboard_navigation::EntryTile,
+    nodes::{spawn_file_node, spawn_folder_node, spawn_source_node},
+    selection::{EntryPath, select_entry},
+};
+
+/// All the filters deciding which [entries](Entry) of the [`DirectoryContent`] are displayed
+#[derive(SystemParam)]
+pub(crate) struct DirectoryContentFilters<'w> {
+    name: Res<'w, DirectoryFilter>,
+    types: Res<'w, DirectoryTypeFilter>,
+}
+
+impl DirectoryContentFilters<'_> {
+    /// Check if an [`Entry`] passes all the filters
+    pub(crate) fn matches(&self, entry: &Entry) -> bool {
+        self.name.matches(entry) && self.types.matches(entry)
+    }
+
+    /// Check if any filter changed since the system last ran
+    pub(crate) fn is_changed(&self) -> bool {
+        self.name.is_changed() || self.types.is_changed()
+    }
+}
 
-use crate::{AssetBrowserLocation, DefaultSourceFilePath, DirectoryContent, Entry, io};
+/// How the tiles are laid out, see [`BrowserViewMode`]
+#[derive(SystemParam)]
+pub(crate) struct TileLayout<'w> {
+    view_mode: Res<'w, BrowserViewMode>,
+    thumbnail_size: Res<'w, ThumbnailSize>,
+}
+
+impl TileLayout<'_> {
+    /// Number of tiles per row, for a content `content_width` pixels wide
+    pub(crate) fn columns(&self, content_width: f32) -> usize {
+        self.view_mode.columns(content_width, *self.thumbnail_size)
+    }
+
+    /// Height of a row of tiles, which is also the width of a grid cell
+    pub(crate) fn cell_size(&self) -> f32 {
+        self.view_mode.cell_size(*self.thumbnail_size)
+    }
+}
+
+/// Icons of the file tiles, the [`PreviewIconOverrides`] take precedence over the [`PlaceholderIcons`]
+#[derive(SystemParam)]
+pub(crate) struct TileIcons<'w> {
+    overrides: Res<'w, PreviewIconOverrides>,
+    placeholders: Res<'w, PlaceholderIcons>,
+}
 
-use crate::ui::nodes::{spawn_file_node, spawn_folder_node, spawn_source_node};
+impl TileIcons<'_> {
+    /// The icon displayed for `file_name`, if any
+    pub(crate) fn icon(&self, file_name: &str) -> Option<&Handle<Image>> {
+        self.overrides
+            .icon(file_name)
+            .or_else(|| self.placeholders.icon(file_name))
+    }
+}
 
 /// Tag for all the asset browser scroll boxes
 #[derive(Component)]
 pub(crate) struct AssetBrowserContent;
 
+/// Rows of tiles kept spawned above and below the viewport, so scrolling doesn't reveal missing tiles
+const OVERSCAN_ROWS: usize = 1;
+
+/// The grid holding the tiles of the displayed [entries](Entry) of the [`DirectoryContent`]
+///
+/// The grid is sized to fit all the entries, but only the tiles around the viewport are spawned,
+/// see [`spawn_visible_tiles`].
+#[derive(Component, Default, Debug)]
+pub(crate) struct TileGrid {
+    /// Number of entries to display
+    total: usize,
+    /// Number of tiles per row
+    columns: usize,
+    /// Range of the entries whose row is around the viewport, the only ones having a tile
+    range: Range<usize>,
+}
+
+impl TileGrid {
+    /// Number of rows needed to display all the entries
+    fn rows(&self) -> usize {
+        self.total.div_ceil(self.columns.max(1))
+    }
+}
+
+/// The range of the `total` entries whose row intersects the viewport, plus [`OVERSCAN_ROWS`] on each side
+///
+/// `scroll_offset` is how far the top of the viewport is below the top of the grid, negative while the nodes above
+/// the grid are visible, and `view_height` the visible height of the scroll box.
+fn visible_range(
+    total: usize,
+    columns: usize,
+    row_height: f32,
+    scroll_offset: f32,
+    view_height: f32,
+) -> Range<usize> {
+    let columns = columns.max(1);
+    let first_row = ((scroll_offset.max(0.0) / row_height) as usize).saturating_sub(OVERSCAN_ROWS);
+    let last_row =
+        ((scroll_offset + view_height).max(0.0) / row_height).ceil() as usize + OVERSCAN_ROWS;
+    (first_row * columns).min(total)..(last_row * columns).min(total)
+}
+
+/// Distance in logical pixels from the top of the `parent` node to the top of its `child`
+///
+/// Used to skip the nodes laid out above the [`TileGrid`], like a directory message or the details header.
+fn top_offset(
+    (parent, parent_transform): (&ComputedNode, &UiGlobalTransform),
+    (child, child_transform): (&ComputedNode, &UiGlobalTransform),
+) -> f32 {
+    let parent_top = parent_transform.translation.y - parent.size().y / 2.0;
+    let child_top = child_transform.translation.y - child.size().y / 2.0;
+    (child_top - parent_top) * child.inverse_scale_factor()
+}
+
 /// Spawn the directory content UI
+///
+/// The content entries are spawned by [`spawn_visible_tiles`] once they are scrolled into view
 pub(crate) fn spawn_directory_content<'a>(
     commands: &'a mut Commands,
-    directory_content: &Res<DirectoryContent>,
     theme: &Res<Theme>,
-    asset_server: &Res<AssetServer>,
-    location: &Res<AssetBrowserLocation>,
 ) -> EntityCommands<'a> {
     let root = commands
         .spawn(Node {
@@ -34,14 +164,6 @@ pub(crate) fn spawn_directory_content<'a>(
             commands
                 .entity(content_list)
                 .insert((AssetBrowserContent, asset_browser_context_menu()));
-            populate_directory_content(
-                commands,
-                content_list,
-                directory_content,
-                asset_server,
-                location,
-                theme,
-            );
         }),
     )
     .insert(ChildOf(root));
@@ -64,7 +186,7 @@ pub(crate) fn refresh_context_menu(
 
 fn asset_browser_context_menu() -> ContextMenu {
     ContextMenu::new([
-        ContextMenuOption::new("Create Folder", |mut commands, _entity| {
+        ContextMenuOption::new("New Folder", |mut commands, _entity| {
             commands.run_system_cached(create_new_folder);
         }),
         ContextMenuOption::new("Create New Script", |mut commands, _entity| {
@@ -73,33 +195,154 @@ fn asset_browser_context_menu() -> ContextMenu {
         ContextMenuOption::new("Open in File Manager", |mut commands, _entity| {
             commands.run_system_cached(open_in_file_manager);
         }),
+        ContextMenuOption::new("Bookmark This Folder", |mut commands, _entity| {
+            commands.run_system_cached(bookmark_current_location);
+        }),
+        ContextMenuOption::new("Find Duplicates", |mut commands, _entity| {
+            commands.run_system_cached(io::task::spawn_find_duplicates_task);
+            commands.run_system_cached(duplicates::spawn_panel);
+        }),
     ])
 }
 
 /// Refresh the UI with the content of the current [`AssetBrowserLocation`]
+///
+/// Only clear the previous content and size the [`TileGrid`], the new [entries](Entry) are spawned by [`spawn_visible_tiles`].
+/// The [`BrowserViewMode::Details`] view starts with the header of its columns.
+#[allow(clippy::too_many_arguments)]
 pub(crate) fn refresh_ui(
     mut commands: Commands,
-    content_list_query: Query<(Entity, Option<&Children>), With<ScrollBoxContent>>,
+    content_list_query: Query<(Entity, &ChildOf, Option<&Children>), With<AssetBrowserContent>>,
     theme: Res<Theme>,
-    asset_server: Res<AssetServer>,
     location: Res<AssetBrowserLocation>,
+    view_mode: Res<BrowserViewMode>,
+    content_order: Res<DirectoryContentOrder>,
     directory_content: Res<DirectoryContent>,
-    mut query_scrollbox: Query<&mut ScrollBox, With<AssetBrowserContent>>,
+    directory_access: Res<DirectoryAccess>,
+    fetch_error: Option<Res<DirectoryFetchError>>,
+    case_collisions: Res<CaseCollisions>,
+    filters: DirectoryContentFilters,
+    mut selected_entry: ResMut<SelectedEntry>,
+    mut query_scrollbox: Query<&mut ScrollBox>,
+    mut refreshed_location: Local<Option<AssetBrowserLocation>>,
 ) {
-    for (content_list_entity, content_list_children) in content_list_query.iter() {
+    let access_denied = *directory_access == DirectoryAccess::Denied;
+    // Nothing was read, the error is displayed instead of an empty directory
+    let fetch_failed = access_denied || fetch_error.is_some();
+    let total = if fetch_failed {
+        0
+    } else {
+        directory_content
+            .0
+            .iter()
+            .filter(|entry| filters.matches(entry))
+            .count()
+    };
+    for (content_list_entity, _, content_list_children) in content_list_query.iter() {
         despawn_content_entries(&mut commands, content_list_entity, content_list_children);
-        populate_directory_content(
-            &mut commands,
-            content_list_entity,
-            &directory_content,
-            &asset_server,
-            &location,
-            &theme,
-        );
+        if access_denied {
+            spawn_directory_message(
+                &mut commands,
+                format!("Access denied: {}", location.path.display()),
+                &theme,
+            )
+            .insert(ChildOf(content_list_entity));
+        } else if let Some(fetch_error) = &fetch_error {
+            spawn_directory_message(
+                &mut commands,
+                format!(
+                    "Couldn't read {}: {}",
+                    fetch_error.path.display(),
+                    fetch_error.message
+                ),
+                &theme,
+            )
+            .insert(ChildOf(content_list_entity));
+        } else if !case_collisions.0.is_empty() {
+            let names = case_collisions
+                .0
+                .iter()
+                .map(|names| names.join(" / "))
+                .collect::<Vec<_>>()
+                .join(", ");
+            spawn_directory_message(
+                &mut commands,
+                format!("Names only differing by case collide on some filesystems: {names}"),
+                &theme,
+            )
+            .insert(ChildOf(content_list_entity));
+        }
+        if *view_mode == BrowserViewMode::Details && !fetch_failed {
+            spawn_details_header(&mut commands, &theme, &content_order)
+                .insert(ChildOf(content_list_entity));
+        }
+        commands.spawn((
+            TileGrid { total, ..default() },
+            Node {
+                width: Val::Percent(100.0),
+                ..default()
+            },
+            ChildOf(content_list_entity),
+        ));
     }
-    // Reset scroll boxes
-    for mut scrollbox in query_scrollbox.iter_mut() {
-        scrollbox.scroll_to_top();
+    selected_entry.0 = None;
+    // Entries streamed in or changed on disk keep the scroll position, other locations and filters start at the top
+    if filters.is_changed() || refreshed_location.as_ref() != Some(&*location) {
+        for (_, content_list_parent, _) in content_list_query.iter() {
+            if let Ok(mut scrollbox) = query_scrollbox.get_mut(content_list_parent.parent()) {
+                scrollbox.scroll_to_top();
+            }
+        }
+        *refreshed_location = Some(location.clone());
+    }
+}
+
+/// Size the [`TileGrid`] for the entries streamed into the [`DirectoryContent`], see [`StreamedEntries`](io::task::StreamedEntries)
+///
+/// Unlike [`refresh_ui`], the grid and its tiles are kept: the tiles and the [`SelectedEntry`] follow their entry
+/// to its new index, and [`spawn_visible_tiles`] spawns the tiles of the new entries.
+pub(crate) fn refresh_streamed_tiles(
+    mut commands: Commands,
+    mut grid_query: Query<(&mut TileGrid, Option<&Children>)>,
+    mut tile_query: Query<(Entity, &mut EntryTile, Option<&EntryPath>)>,
+    location: Res<AssetBrowserLocation>,
+    directory_content: Res<DirectoryContent>,
+    filters: DirectoryContentFilters,
+    mut selected_entry: ResMut<SelectedEntry>,
+) {
+    let displayed = directory_content
+        .0
+        .iter()
+        .filter(|entry| filters.matches(entry))
+        .enumerate();
+    let mut total = 0;
+    let mut indices = HashMap::new();
+    for (index, entry) in displayed {
+        total += 1;
+        if let Entry::Folder(name) | Entry::File(name) = entry {
+            indices.insert(location.path.join(name), index);
+        }
+    }
+    let mut selected = None;
+    for (mut grid, tiles) in grid_query.iter_mut() {
+        grid.total = total;
+        let mut tiles = tile_query.iter_many_mut(tiles.into_iter().flatten());
+        while let Some((tile, mut entry_tile, path)) = tiles.fetch_next() {
+            match path.and_then(|EntryPath(path)| indices.get(path)) {
+                Some(&index) => {
+                    if selected_entry.0 == Some(entry_tile.0) {
+                        selected = Some(index);
+                    }
+                    if entry_tile.0 != index {
+                        entry_tile.0 = index;
+                    }
+                }
+                None => commands.entity(tile).despawn(),
+            }
+        }
+    }
+    if selected_entry.0 != selected {
+        selected_entry.0 = selected;
     }
 }
 
@@ -113,84 +356,337 @@ fn despawn_content_entries(commands: &mut Commands, container: Entity, entries:
     }
 }
 
-/// Spawn all the content [entries](Entry) based on [`DirectoryContent`]
-fn populate_directory_content(
-    commands: &mut Commands,
-    parent_entity: Entity,
-    directory_content: &Res<DirectoryContent>,
-    asset_server: &Res<AssetServer>,
-    location: &Res<AssetBrowserLocation>,
-    theme: &Res<Theme>,
+/// Spawn the tiles of the displayed [entries](Entry) around the viewport, and despawn the ones scrolled away
+///
+/// The number of tiles only depends on the viewport size, whatever the number of entries.
+/// At most [`TileSpawnBudget`] tiles are spawned per frame.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn spawn_visible_tiles(
+    mut commands: Commands,
+    mut grid_query: Query<(
+        Entity,
+        &mut TileGrid,
+        &ComputedNode,
+        &UiGlobalTransform,
+        &ChildOf,
+        Option<&Children>,
+    )>,
+    content_query: Query<(&ComputedNode, &UiGlobalTransform, &ChildOf), With<AssetBrowserContent>>,
+    scroll_box_query: Query<(&ScrollBox, &ComputedNode)>,
+    tile_query: Query<(Entity, &EntryTile)>,
+    theme: Res<Theme>,
+    asset_server: Res<AssetServer>,
+    location: Res<AssetBrowserLocation>,
+    directory_content: Res<DirectoryContent>,
+    entry_details: Res<DirectoryEntryDetails>,
+    filters: DirectoryContentFilters,
+    budget: Res<TileSpawnBudget>,
+    tile_layout: TileLayout,
+    tile_background: TileBackground,
+    extension_badges: Res<ExtensionBadges>,
+    tile_icons: TileIcons,
 ) {
-    for entry in &directory_content.0 {
-        match entry {
-            Entry::Source(id) => {
-                spawn_source_node(commands, id, asset_server, theme).insert(ChildOf(parent_entity));
+    let cell_size = tile_layout.cell_size();
+    for (grid_entity, mut grid, grid_node, grid_transform, grid_parent, tiles) in
+        grid_query.iter_mut()
+    {
+        let Ok((content_node, content_transform, content_parent)) =
+            content_query.get(grid_parent.parent())
+        else {
+            continue;
+        };
+        let Ok((scroll_box, scroll_box_node)) = scroll_box_query.get(content_parent.parent())
+        else {
+            continue;
+        };
+        let content_width = content_node.size().x * content_node.inverse_scale_factor();
+        let view_height = scroll_box_node.size().y * scroll_box_node.inverse_scale_factor();
+        let columns = tile_layout.columns(content_width);
+        let grid_top = top_offset(
+            (content_node, content_transform),
+            (grid_node, grid_transform),
+        );
+        let range = visible_range(
+            grid.total,
+            columns,
+            cell_size,
+            scroll_box.scroll_offset().y - grid_top,
+            view_height,
+        );
+        if grid.columns != columns || grid.range != range {
+            grid.columns = columns;
+            grid.range = range.clone();
+        }
+
+        let mut spawned = HashSet::new();
+        for (tile, EntryTile(index)) in tile_query.iter_many(tiles.into_iter().flatten()) {
+            if range.contains(index) {
+                spawned.insert(*index);
+            } else {
+                commands.entity(tile).despawn();
             }
-            Entry::Folder(name) => {
-                spawn_folder_node(commands, name.clone(), asset_server, location, theme)
-                    .insert(ChildOf(parent_entity));
+        }
+        let batch = budget.next_batch(range, &spawned);
+        let (Some(&first), Some(&last)) = (batch.first(), batch.last()) else {
+            continue;
+        };
+        for (index, entry) in directory_content
+            .0
+            .iter()
+            .filter(|entry| filters.matches(entry))
+            .enumerate()
+            .skip(first)
+            .take(last + 1 - first)
+            .filter(|(index, _)| batch.binary_search(index).is_ok())
+        {
+            let mut tile = spawn_entry_node(
+                &mut commands,
+                entry,
+                &asset_server,
+                &location,
+                &theme,
+                tile_background.checkerboard(),
+                extension_badges.0,
+                &tile_icons,
+                *tile_layout.view_mode,
+                entry_details.get(entry),
+            );
+            tile.insert((EntryTile(index), ChildOf(grid_entity)));
+            if let Entry::Folder(name) | Entry::File(name) = entry {
+                tile.insert(EntryPath(location.path.join(name)))
+                    .observe(select_entry);
             }
-            Entry::File(name) => {
-                spawn_file_node(commands, name.clone(), asset_server, location, theme)
-                    .insert(ChildOf(parent_entity));
+            if let Entry::File(name) = entry {
+                tile.insert(DraggableAsset {
+                    path: location.path.join(name),
+                    source_id: location.source_id.clone(),
+                })
+                .observe(drag::start_drag)
+                .observe(drag::move_drag_ghost)
+                .observe(drag::end_drag);
             }
         }
     }
 }
 
+/// Place the tiles in their cell of the [`TileGrid`], and size the grid to fit all the displayed entries
+pub(crate) fn place_tiles(
+    tile_layout: TileLayout,
+    mut grid_query: Query<(&TileGrid, &mut Node, Option<&Children>), Without<EntryTile>>,
+    mut tile_query: Query<(&EntryTile, &mut Node), Without<TileGrid>>,
+) {
+    let cell_size = tile_layout.cell_size();
+    for (grid, mut grid_node, tiles) in grid_query.iter_mut() {
+        let height = Val::Px(grid.rows() as f32 * cell_size);
+        if grid_node.height != height {
+            grid_node.height = height;
+        }
+        let columns = grid.columns.max(1);
+        let mut tiles = tile_query.iter_many_mut(tiles.into_iter().flatten());
+        while let Some((EntryTile(index), mut node)) = tiles.fetch_next() {
+            let left = Val::Px((index % columns) as f32 * cell_size);
+            let top = Val::Px((index / columns) as f32 * cell_size);
+            if node.position_type != PositionType::Absolute || node.left != left || node.top != top
+            {
+                node.position_type = PositionType::Absolute;
+                node.left = left;
+                node.top = top;
+            }
+        }
+    }
+}
+
+/// Spawn the node corresponding to an [`Entry`]
+///
+/// In the [`BrowserViewMode::Details`] view, the node is laid out as a row ending with the entry `details`,
+/// which replace the extension badge and the checkerboard.
+#[allow(clippy::too_many_arguments)]
+fn spawn_entry_node<'a>(
+    commands: &'a mut Commands,
+    entry: &Entry,
+    asset_server: &Res<AssetServer>,
+    location: &Res<AssetBrowserLocation>,
+    theme: &Res<Theme>,
+    checkerboard: Option<&CheckerboardImage>,
+    show_extension_badge: bool,
+    tile_icons: &TileIcons,
+    view_mode: BrowserViewMode,
+    details: Option<&EntryDetails>,
+) -> EntityCommands<'a> {
+    let is_grid = view_mode == BrowserViewMode::Grid;
+    let node = match entry {
+        Entry::Source(id) => spawn_source_node(commands, id, asset_server, theme).id(),
+        Entry::Folder(name) => {
+            spawn_folder_node(commands, name.clone(), asset_server, location, theme).id()
+        }
+        Entry::File(name) => spawn_file_node(
+            commands,
+            name.clone(),
+            asset_server,
+            location,
+            theme,
+            checkerboard.filter(|_| is_grid),
+            show_extension_badge && is_grid,
+            tile_icons,
+        )
+        .id(),
+    };
+    if !is_grid {
+        spawn_detail_cells(commands, node, entry, details, theme);
+    }
+    commands.entity(node)
+}
+
+/// Spawn a message displayed in place of the directory content
+fn spawn_directory_message<'a>(
+    commands: &'a mut Commands,
+    message: String,
+    theme: &Res<Theme>,
+) -> EntityCommands<'a> {
+    commands.spawn((
+        Text::new(message),
+        TextFont {
+            font: theme.text.font.clone(),
+            font_size: 10.0,
+            ..default()
+        },
+        TextColor(theme.text.low_priority),
+        Node {
+            margin: UiRect::all(Val::Px(10.0)),
+            ..default()
+        },
+    ))
+}
+
+/// Create a uniquely named folder in the current [`AssetBrowserLocation`] and refresh the directory content
 pub(crate) fn create_new_folder(
     mut commands: Commands,
     default_source_file_path: Res<DefaultSourceFilePath>,
     location: Res<AssetBrowserLocation>,
-    directory_content: Res<DirectoryContent>,
 ) {
     if location.source_id != Some(AssetSourceId::Default) {
-        panic!("Cannot create folder: Invalid source id, make sure your inside the Default source");
+        eprintln!(
+            "Cannot create folder: Invalid source id, make sure your inside the Default source"
+        );
+        return;
     }
     let mut path = default_source_file_path.0.clone();
     path.push(location.path.as_path());
     match io::create_new_folder(path) {
-        Ok(folder_name) => {
-            let mut updated_content = directory_content.0.clone();
-            updated_content.push(Entry::Folder(folder_name));
-            commands.insert_resource(DirectoryContent(updated_content));
-        }
+        Ok(_) => commands.run_system_cached(io::task::fetch_directory_content),
         Err(e) => eprintln!("Failed to create directory: {e}"),
     }
 }
 
-pub(crate) fn delete_folder(
-    folder_entity: In<Entity>,
-    query_children: Query<&Children>,
-    query_text: Query<&Text>,
+/// Bookmark the current [`AssetBrowserLocation`], labelled with its folder name
+pub(crate) fn bookmark_current_location(
+    location: Res<AssetBrowserLocation>,
+    mut bookmarks: ResMut<BrowserBookmarks>,
+) {
+    bookmarks.add(BrowserBookmarks::default_label(&location), &location);
+}
+
+/// Delete the entry of a tile, or all the selected entries if the tile is part of the [`Selection`]
+///
+/// Entries are moved to the OS trash when the `trash` feature is enabled,
+/// otherwise deleting several entries at once has to be confirmed first.
+pub(crate) fn delete_entries(
+    tile: In<Entity>,
     mut commands: Commands,
-    default_source_file_path: Res<DefaultSourceFilePath>,
+    query_path: Query<&EntryPath>,
     location: Res<AssetBrowserLocation>,
-    directory_content: Res<DirectoryContent>,
+    selection: Res<Selection>,
+    theme: Res<Theme>,
 ) {
     if location.source_id != Some(AssetSourceId::Default) {
-        panic!("Cannot delete folder: Invalid source id, make sure your inside the Default source");
+        eprintln!("Cannot delete: Invalid source id, make sure your inside the Default source");
+        return;
     }
-    let folder_children = query_children.get(*folder_entity).unwrap();
-    let folder_name = query_text
-        .get(*folder_children.get(1).unwrap())
-        .unwrap()
-        .0
-        .clone();
-    let mut path = default_source_file_path.0.clone();
-    path.push(location.path.as_path());
-    path.push(folder_name.clone());
-    match io::delete_folder(path) {
-        Ok(_) => {
-            let mut updated_content = directory_content.0.clone();
-            updated_content.retain(|entry| match entry {
-                Entry::Folder(name) => name != &folder_name,
-                _ => true,
-            });
-            commands.insert_resource(DirectoryContent(updated_content));
+    let Ok(EntryPath(tile_path)) = query_path.get(*tile) else {
+        return;
+    };
+    let paths: Vec<PathBuf> = if selection.0.contains(tile_path) {
+        selection.0.iter().cloned().collect()
+    } else {
+        vec![tile_path.clone()]
+    };
+    if deletion_needs_confirmation(paths.len()) {
+        spawn_delete_confirmation(&mut commands, &theme, paths);
+    } else {
+        commands.run_system_cached_with(delete_paths, paths);
+    }
+}
+
+/// Delete entries given by their path relative to the Default source root,
+/// and remove them from the [`Selection`] and the [`DuplicateGroups`]
+pub(crate) fn delete_paths(
+    paths: In<Vec<PathBuf>>,
+    mut commands: Commands,
+    default_source_file_path: Res<DefaultSourceFilePath>,
+    mut selection: ResMut<Selection>,
+    mut duplicate_groups: ResMut<DuplicateGroups>,
+) {
+    for path in paths.iter() {
+        selection.0.remove(path);
+        if duplicate_groups.contains(path) {
+            duplicate_groups.remove(path);
+        }
+        let absolute_path = default_source_file_path.0.join(path);
+        let result = if absolute_path.is_dir() {
+            io::delete_folder(absolute_path)
+        } else {
+            io::delete_file(absolute_path)
+        };
+        if let Err(e) = result {
+            eprintln!("Failed to delete {}: {e}", path.display());
         }
-        Err(e) => eprintln!("Failed to delete directory: {e}"),
+    }
+    commands.run_system_cached(io::task::fetch_directory_content);
+}
+
+/// Copy the absolute path on disk of an [`Entry`] of the current location to the clipboard
+#[cfg(feature = "clipboard")]
+pub(crate) fn copy_absolute_path(
+    entry: In<Entry>,
+    default_source_file_path: Res<DefaultSourceFilePath>,
+    location: Res<AssetBrowserLocation>,
+    clipboard: Option<ResMut<BevyClipboard>>,
+) {
+    match location.absolute_path(&entry, &default_source_file_path) {
+        Some(path) => copy_to_clipboard(clipboard, path.display().to_string()),
+        None => eprintln!(
+            "Cannot copy absolute path: {:?} isn't in the Default source",
+            *entry
+        ),
+    }
+}
+
+/// Copy the [`AssetPath`](bevy::asset::AssetPath) of an [`Entry`] of the current location to the clipboard
+#[cfg(feature = "clipboard")]
+pub(crate) fn copy_asset_path(
+    entry: In<Entry>,
+    location: Res<AssetBrowserLocation>,
+    clipboard: Option<ResMut<BevyClipboard>>,
+) {
+    match location.asset_path(&entry) {
+        Some(asset_path) => copy_to_clipboard(clipboard, asset_path.to_string()),
+        None => eprintln!(
+            "Cannot copy asset path: {:?} isn't in an asset source",
+            *entry
+        ),
+    }
+}
+
+/// Copy `text` to the clipboard, logging the copied text so the user gets a confirmation
+#[cfg(feature = "clipboard")]
+fn copy_to_clipboard(clipboard: Option<ResMut<BevyClipboard>>, text: String) {
+    let Some(mut clipboard) = clipboard else {
+        eprintln!("Cannot copy {text}: the clipboard isn't available");
+        return;
+    };
+    match clipboard.set_text(text.clone()) {
+        Ok(()) => eprintln!("Copied {text} to the clipboard"),
+        Err(e) => eprintln!("Failed to copy {text} to the clipboard: {e}"),
     }
 }
 
@@ -230,63 +726,86 @@ pub(crate) fn open_in_file_manager(
     }
 }
 
-// TODO: fix this, doesn't yet work, it opens the file instead of revealing it in the file manager (at least on linux)
-#[allow(dead_code)]
+/// Reveal an [`Entry`] of the current location in the OS file manager, opening it for folders
 pub(crate) fn reveal_in_file_manager(
-    file_entity: In<Entity>,
-    query_children: Query<&Children>,
-    query_text: Query<&Text>,
+    entry: In<Entry>,
     default_source_file_path: Res<DefaultSourceFilePath>,
     location: Res<AssetBrowserLocation>,
 ) {
-    if location.source_id != Some(AssetSourceId::Default) {
-        panic!("Cannot delete file: Invalid source id, make sure your inside the Default source");
-    }
-    let file_children = query_children.get(*file_entity).unwrap();
-    let file_name = query_text
-        .get(*file_children.get(1).unwrap())
-        .unwrap()
-        .0
-        .clone();
-    let mut path = default_source_file_path.0.clone();
-    path.push(location.path.as_path());
-    path.push(file_name.clone());
-    match io::open_in_file_manager(path) {
-        Ok(_) => {}
-        Err(e) => eprintln!("Failed to reveal in file manager: {e}"),
+    let Some(path) = location.absolute_path(&entry, &default_source_file_path) else {
+        eprintln!(
+            "Cannot reveal in file manager: {:?} isn't in the Default source",
+            *entry
+        );
+        return;
+    };
+    if let Err(e) = io::reveal_in_file_manager(path) {
+        eprintln!("Failed to reveal {:?} in file manager: {e}", *entry);
     }
 }
 
-pub(crate) fn delete_file(
-    file_entity: In<Entity>,
-    query_children: Query<&Children>,
-    query_text: Query<&Text>,
-    mut commands: Commands,
-    default_source_file_path: Res<DefaultSourceFilePath>,
-    location: Res<AssetBrowserLocation>,
-    directory_content: Res<DirectoryContent>,
-) {
-    if location.source_id != Some(AssetSourceId::Default) {
-        panic!("Cannot delete file: Invalid source id, make sure your inside the Default source");
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_rows_around_the_viewport_are_visible() {
+        // 10 000 entries, 4 columns, rows of 60px and a viewport of 300px
+        assert_eq!(visible_range(10_000, 4, 60.0, 0.0, 300.0), 0..24);
+        assert_eq!(visible_range(10_000, 4, 60.0, 6000.0, 300.0), 396..424);
+        assert_eq!(
+            visible_range(10_000, 4, 60.0, 149_990.0, 300.0),
+            9992..10_000
+        );
+        assert_eq!(visible_range(10, 4, 60.0, 0.0, 300.0), 0..10);
+        assert_eq!(visible_range(0, 4, 60.0, 0.0, 300.0), 0..0);
+        // A viewport that isn't laid out yet still shows the first rows
+        assert_eq!(visible_range(100, 0, 60.0, 0.0, 0.0), 0..1);
+        // The grid starts 120px below the top of the viewport, under a message and the details header
+        assert_eq!(visible_range(10_000, 4, 60.0, -120.0, 300.0), 0..16);
+        assert_eq!(visible_range(10_000, 4, 60.0, -400.0, 300.0), 0..4);
     }
-    let file_children = query_children.get(*file_entity).unwrap();
-    let file_name = query_text
-        .get(*file_children.get(1).unwrap())
-        .unwrap()
-        .0
-        .clone();
-    let mut path = default_source_file_path.0.clone();
-    path.push(location.path.as_path());
-    path.push(file_name.clone());
-    match io::delete_file(path) {
-        Ok(_) => {
-            let mut updated_content = directory_content.0.clone();
-            updated_content.retain(|entry| match entry {
-                Entry::File(name) => name != &file_name,
-                _ => true,
-            });
-            commands.insert_resource(DirectoryContent(updated_content));
-        }
-        Err(e) => eprintln!("Failed to delete file: {e}"),
+
+    #[test]
+    fn streamed_entries_keep_the_spawned_tiles() {
+        let file = |name: &str| Entry::File(name.to_string());
+        let mut app = App::new();
+        app.insert_resource(AssetBrowserLocation {
+            source_id: Some(AssetSourceId::Default),
+            path: PathBuf::from("textures"),
+        })
+        .insert_resource(DirectoryContent(vec![file("b.png"), file("d.png")]))
+        .init_resource::<DirectoryFilter>()
+        .init_resource::<DirectoryTypeFilter>()
+        .insert_resource(SelectedEntry(Some(1)))
+        .add_systems(Update, refresh_streamed_tiles);
+        let grid = app
+            .world_mut()
+            .spawn(TileGrid {
+                total: 2,
+                ..default()
+            })
+            .id();
+        let mut spawn_tile = |name: &str, index: usize| {
+            app.world_mut()
+                .spawn((
+                    EntryTile(index),
+                    EntryPath(PathBuf::from("textures").join(name)),
+                    ChildOf(grid),
+                ))
+                .id()
+        };
+        let b_tile = spawn_tile("b.png", 0);
+        let d_tile = spawn_tile("d.png", 1);
+
+        app.world_mut().resource_mut::<DirectoryContent>().0 =
+            ["a.png", "b.png", "c.png", "d.png"].map(file).to_vec();
+        app.update();
+
+        let world = app.world();
+        assert_eq!(world.get::<TileGrid>(grid).unwrap().total, 4);
+        assert_eq!(world.get::<EntryTile>(b_tile).unwrap().0, 1);
+        assert_eq!(world.get::<EntryTile>(d_tile).unwrap().0, 3);
+        assert_eq!(*world.resource::<SelectedEntry>(), SelectedEntry(Some(3)));
     }
 }
@@ -0,0 +1,127 @@
+//! Drag file tiles out of the asset browser, see [`DraggableAsset`]
+//!
+//! Other panes accept dropped assets by observing [`Pointer<DragDrop>`]
+//! and reading the [`DraggableAsset`] of the dropped entity.
+
+use std::path::PathBuf;
+
+use bevy::{
+    asset::{AssetPath, io::AssetSourceId},
+    prelude::*,
+};
+
+use super::{quick_look::QuickLook, thumbnail_size::Thumbnail};
+
+/// Size of the preview following the cursor while dragging
+const DRAG_GHOST_SIZE: f32 = 48.0;
+
+/// The asset displayed by a file tile, the payload of the tile when it's dragged
+#[derive(Component, Debug, Clone, PartialEq, Eq)]
+pub struct DraggableAsset {
+    /// Path of the file relative to its asset source root
+    pub path: PathBuf,
+    /// The asset source containing the file
+    pub source_id: Option<AssetSourceId<'static>>,
+}
+
+impl DraggableAsset {
+    /// Get the [`AssetPath`] to load the dragged asset with, if it belongs to an asset source
+    pub fn asset_path(&self) -> Option<AssetPath<'static>> {
+        let source_id = self.source_id.clone()?;
+        Some(AssetPath::from_path_buf(self.path.clone()).with_source(source_id))
+    }
+}
+
+/// The preview of the dragged tile following the cursor
+#[derive(Component)]
+pub struct DragGhost;
+
+/// Spawn a [`DragGhost`] with the thumbnail of the dragged tile
+pub(crate) fn start_drag(
+    trigger: On<Pointer<DragStart>>,
+    mut commands: Commands,
+    children_query: Query<&Children>,
+    thumbnail_query: Query<&ImageNode, With<Thumbnail>>,
+    mut quick_look: ResMut<QuickLook>,
+) {
+    if trigger.event().button != PointerButton::Primary {
+        return;
+    }
+    // The tile is being moved, not inspected
+    quick_look.cancel(&mut commands);
+    let Some(thumbnail) = children_query
+        .iter_descendants(trigger.target())
+        .find_map(|child| thumbnail_query.get(child).ok())
+    else {
+        return;
+    };
+    let position = trigger.pointer_location.position;
+    commands.spawn((
+        DragGhost,
+        ImageNode::new(thumbnail.image.clone()).with_color(Color::WHITE.with_alpha(0.7)),
+        Node {
+            position_type: PositionType::Absolute,
+            left: Val::Px(position.x - DRAG_GHOST_SIZE / 2.0),
+            top: Val::Px(position.y - DRAG_GHOST_SIZE / 2.0),
+            width: Val::Px(DRAG_GHOST_SIZE),
+            height: Val::Px(DRAG_GHOST_SIZE),
+            ..default()
+        },
+        GlobalZIndex(i32::MAX - 1),
+        // Let the drop targets under the cursor receive the pointer events
+        Pickable::IGNORE,
+    ));
+}
+
+/// Move the [`DragGhost`] with the cursor
+pub(crate) fn move_drag_ghost(
+    trigger: On<Pointer<Drag>>,
+    mut ghost_query: Query<&mut Node, With<DragGhost>>,
+) {
+    let position = trigger.pointer_location.position;
+    for mut node in ghost_query.iter_mut() {
+        node.left = Val::Px(position.x - DRAG_GHOST_SIZE / 2.0);
+        node.top = Val::Px(position.y - DRAG_GHOST_SIZE / 2.0);
+    }
+}
+
+/// Remove the [`DragGhost`] once the tile is dropped
+pub(crate) fn end_drag(
+    _trigger: On<Pointer<DragEnd>>,
+    mut commands: Commands,
+    ghost_query: Query<Entity, With<DragGhost>>,
+) {
+    for ghost in ghost_query.iter() {
+        commands.entity(ghost).despawn();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn draggable_asset_path_includes_its_source() {
+        let asset = DraggableAsset {
+            path: PathBuf::from("textures/hero.png"),
+            source_id: Some(AssetSourceId::Name("remote".into())),
+        };
+        assert_eq!(
+            asset.asset_path().unwrap().to_string(),
+            "remote://textures/hero.png"
+        );
+        let default_asset = DraggableAsset {
+            source_id: Some(AssetSourceId::Default),
+            ..asset.clone()
+        };
+        assert_eq!(
+            default_asset.asset_path().unwrap().to_string(),
+            "textures/hero.png"
+        );
+        let sourceless = DraggableAsset {
+            source_id: None,
+            ..asset
+        };
+        assert_eq!(sourceless.asset_path(), None);
+    }
+}
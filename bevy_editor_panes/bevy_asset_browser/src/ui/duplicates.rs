@@ -0,0 +1,274 @@
+//! Panel presenting the [`DuplicateGroups`] found in the current source
+
+use std::{path::PathBuf, sync::atomic::Ordering};
+
+use bevy::{
+    asset::io::AssetSourceId, feathers::cursor::EntityCursor, prelude::*, window::SystemCursorIcon,
+};
+use bevy_editor_styles::Theme;
+
+use crate::{
+    AssetBrowserLocation, DefaultSourceFilePath, DuplicateGroups, io, io::task::FindDuplicatesTask,
+};
+
+use super::{
+    delete_confirmation::{deletion_is_permanent, spawn_delete_confirmation},
+    directory_content::delete_paths,
+    top_bar::{PATH_SEGMENT_BACKGROUND_COLOR, TopBarNode},
+};
+
+/// The root node of the duplicates panel
+#[derive(Component)]
+pub struct DuplicatesPanelNode;
+
+/// Text showing the progress and outcome of the scan
+#[derive(Component)]
+struct DuplicatesStatusText;
+
+/// Container of all the duplicate groups rows
+#[derive(Component)]
+struct DuplicatesListNode;
+
+/// Spawn the duplicates panel under the directory content, unless it's already opened
+pub(crate) fn spawn_panel(
+    mut commands: Commands,
+    theme: Res<Theme>,
+    location: Res<AssetBrowserLocation>,
+    top_bar_query: Query<&ChildOf, With<TopBarNode>>,
+    panel_query: Query<(), With<DuplicatesPanelNode>>,
+) {
+    if location.source_id != Some(AssetSourceId::Default) || !panel_query.is_empty() {
+        return;
+    }
+    for top_bar_parent in top_bar_query.iter() {
+        let panel = commands
+            .spawn((
+                DuplicatesPanelNode,
+                Node {
+                    width: Val::Percent(100.0),
+                    max_height: Val::Percent(50.0),
+                    flex_direction: FlexDirection::Column,
+                    flex_shrink: 0.0,
+                    padding: UiRect::all(Val::Px(5.0)),
+                    overflow: Overflow::clip_y(),
+                    ..default()
+                },
+                theme.pane.header_background_color,
+                ChildOf(top_bar_parent.parent()),
+            ))
+            .id();
+        let header = commands
+            .spawn((
+                Node {
+                    flex_direction: FlexDirection::Row,
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::SpaceBetween,
+                    ..default()
+                },
+                ChildOf(panel),
+            ))
+            .id();
+        commands.spawn((
+            DuplicatesStatusText,
+            Text::new("Scanning for duplicates..."),
+            text_font(&theme),
+            TextColor(theme.text.text_color),
+            ChildOf(header),
+        ));
+        spawn_button(&mut commands, &theme, "Close")
+            .insert(ChildOf(header))
+            .observe(
+                |_trigger: On<Pointer<Release>>,
+                 mut commands: Commands,
+                 panel_query: Query<Entity, With<DuplicatesPanelNode>>| {
+                    for panel in panel_query.iter() {
+                        commands.entity(panel).despawn();
+                    }
+                },
+            );
+        commands.spawn((
+            DuplicatesListNode,
+            Node {
+                flex_direction: FlexDirection::Column,
+                ..default()
+            },
+            ChildOf(panel),
+        ));
+    }
+}
+
+/// Display how many files have been hashed by the running [`FindDuplicatesTask`]
+pub(crate) fn update_scan_progress(
+    task_query: Query<&FindDuplicatesTask>,
+    mut status_query: Query<&mut Text, With<DuplicatesStatusText>>,
+) {
+    let Ok(task) = task_query.single() else {
+        return;
+    };
+    let scanned = task.progress.scanned.load(Ordering::Relaxed);
+    let total = task.progress.total.load(Ordering::Relaxed);
+    for mut status in status_query.iter_mut() {
+        status.0 = format!("Scanning for duplicates... {scanned}/{total}");
+    }
+}
+
+/// Regenerate the duplicate groups rows from [`DuplicateGroups`]
+pub(crate) fn refresh_ui(
+    mut commands: Commands,
+    theme: Res<Theme>,
+    duplicate_groups: Res<DuplicateGroups>,
+    list_query: Query<(Entity, Option<&Children>), With<DuplicatesListNode>>,
+    mut status_query: Query<&mut Text, With<DuplicatesStatusText>>,
+    task_query: Query<(), With<FindDuplicatesTask>>,
+) {
+    if !task_query.is_empty() {
+        return;
+    }
+    for mut status in status_query.iter_mut() {
+        status.0 = match duplicate_groups.0.len() {
+            0 => "No duplicates found".to_string(),
+            count => format!("{count} groups of duplicates found"),
+        };
+    }
+    for (list_entity, list_children) in list_query.iter() {
+        if let Some(children) = list_children {
+            for child in children.iter() {
+                commands.entity(child).despawn();
+            }
+            commands.entity(list_entity).remove::<Children>();
+        }
+        for (group_index, group) in duplicate_groups.0.iter().enumerate() {
+            let group_header = commands
+                .spawn((
+                    Node {
+                        flex_direction: FlexDirection::Row,
+                        align_items: AlignItems::Center,
+                        margin: UiRect::top(Val::Px(5.0)),
+                        ..default()
+                    },
+                    ChildOf(list_entity),
+                ))
+                .id();
+            commands.spawn((
+                Text::new(format!(
+                    "{} identical files ({} bytes each)",
+                    group.paths.len(),
+                    group.size
+                )),
+                text_font(&theme),
+                TextColor(theme.text.low_priority),
+                ChildOf(group_header),
+            ));
+            spawn_button(&mut commands, &theme, "Keep all")
+                .insert(ChildOf(group_header))
+                .observe(
+                    move |_trigger: On<Pointer<Release>>,
+                          mut duplicate_groups: ResMut<DuplicateGroups>| {
+                        if group_index < duplicate_groups.0.len() {
+                            duplicate_groups.0.remove(group_index);
+                        }
+                    },
+                );
+            for path in &group.paths {
+                spawn_duplicate_row(&mut commands, &theme, path.clone())
+                    .insert(ChildOf(list_entity));
+            }
+        }
+    }
+}
+
+/// Spawn the row of one duplicated file, with a button to delete it
+///
+/// The file is only deleted while another file of its group still has the same bytes,
+/// and permanent deletions are confirmed first.
+fn spawn_duplicate_row<'a>(
+    commands: &'a mut Commands,
+    theme: &Theme,
+    path: PathBuf,
+) -> EntityCommands<'a> {
+    let row = commands
+        .spawn(Node {
+            flex_direction: FlexDirection::Row,
+            align_items: AlignItems::Center,
+            padding: UiRect::left(Val::Px(10.0)),
+            ..default()
+        })
+        .id();
+    commands.spawn((
+        Text::new(path.to_string_lossy().to_string()),
+        text_font(theme),
+        TextColor(theme.text.text_color),
+        ChildOf(row),
+    ));
+    spawn_button(commands, theme, "Delete")
+        .insert(ChildOf(row))
+        .observe(
+            move |trigger: On<Pointer<Release>>,
+                  mut commands: Commands,
+                  theme: Res<Theme>,
+                  default_source_file_path: Res<DefaultSourceFilePath>,
+                  duplicate_groups: Res<DuplicateGroups>| {
+                if trigger.event().button != PointerButton::Primary {
+                    return;
+                }
+                // The files may have changed on disk since the scan
+                let root = &default_source_file_path.0;
+                let has_copy = duplicate_groups
+                    .0
+                    .iter()
+                    .filter(|group| group.paths.contains(&path))
+                    .flat_map(|group| group.paths.iter())
+                    .filter(|other| **other != path)
+                    .any(|other| {
+                        io::duplicates::same_content(&root.join(&path), &root.join(other))
+                            .unwrap_or(false)
+                    });
+                if !has_copy {
+                    eprintln!(
+                        "Cannot delete {}: no identical copy of it is left, scan for duplicates again",
+                        path.display()
+                    );
+                    return;
+                }
+                let paths = vec![path.clone()];
+                if deletion_is_permanent() {
+                    spawn_delete_confirmation(&mut commands, &theme, paths);
+                } else {
+                    commands.run_system_cached_with(delete_paths, paths);
+                }
+            },
+        );
+    commands.entity(row)
+}
+
+/// Spawn a small text button used by the panel actions
+fn spawn_button<'a>(commands: &'a mut Commands, theme: &Theme, label: &str) -> EntityCommands<'a> {
+    let button = commands
+        .spawn((
+            Button,
+            Node {
+                padding: UiRect::axes(Val::Px(10.0), Val::Px(1.0)),
+                margin: UiRect::horizontal(Val::Px(5.0)),
+                ..default()
+            },
+            BackgroundColor(PATH_SEGMENT_BACKGROUND_COLOR),
+            theme.general.border_radius,
+            EntityCursor::System(SystemCursorIcon::Pointer),
+        ))
+        .id();
+    commands.spawn((
+        Text::new(label),
+        text_font(theme),
+        TextColor(theme.text.text_color),
+        ChildOf(button),
+    ));
+    commands.entity(button)
+}
+
+fn text_font(theme: &Theme) -> TextFont {
+    TextFont {
+        font: theme.text.font.clone(),
+        font_size: 10.0,
+        ..default()
+    }
+}
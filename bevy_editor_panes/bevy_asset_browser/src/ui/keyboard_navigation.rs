@@ -0,0 +1,128 @@
+//! Navigate the directory content grid with the keyboard
+//!
+//! Arrow keys move the [`SelectedEntry`], Enter opens the selected entry, and Backspace goes up a directory.
+
+use bevy::{input_focus::InputFocus, prelude::*, ui::RelativeCursorPosition};
+use bevy_editor_styles::colors::EditorColors;
+use bevy_scroll_box::ScrollBox;
+
+use crate::{AssetBrowserLocation, DirectoryContent, Entry, RecentAssets, SelectedEntry, io};
+
+use super::{
+    AssetBrowserNode,
+    directory_content::{AssetBrowserContent, DirectoryContentFilters, TileLayout},
+    top_bar::has_parent_directory,
+};
+
+/// The index of a tile among the displayed [entries](Entry)
+#[derive(Component)]
+pub(crate) struct EntryTile(pub(crate) usize);
+
+/// Move the [`SelectedEntry`] with the arrow keys, open it with Enter and go up a directory with Backspace
+///
+/// Nothing happens while another widget, like the search field, holds the input focus,
+/// or while the cursor isn't over the asset browser, so the keys of the other panes aren't taken.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn keyboard_navigation(
+    mut commands: Commands,
+    keys: Res<ButtonInput<KeyCode>>,
+    input_focus: Option<Res<InputFocus>>,
+    browser_query: Query<&RelativeCursorPosition, With<AssetBrowserNode>>,
+    mut selected_entry: ResMut<SelectedEntry>,
+    mut location: ResMut<AssetBrowserLocation>,
+    directory_content: Res<DirectoryContent>,
+    filters: DirectoryContentFilters,
+    tile_layout: TileLayout,
+    mut recent_assets: ResMut<RecentAssets>,
+    content_query: Query<(&ComputedNode, &ChildOf), With<AssetBrowserContent>>,
+    mut scroll_box_query: Query<(&mut ScrollBox, &ComputedNode)>,
+) {
+    // A focus change this frame means the key was meant for the previously focused widget
+    if input_focus.is_some_and(|input_focus| input_focus.0.is_some() || input_focus.is_changed()) {
+        return;
+    }
+    if !browser_query
+        .iter()
+        .any(RelativeCursorPosition::cursor_over)
+    {
+        return;
+    }
+    let Some((content_node, content_parent)) = content_query.iter().next() else {
+        return;
+    };
+
+    if keys.just_pressed(KeyCode::Backspace) && has_parent_directory(&location) {
+        location.path.pop();
+        commands.run_system_cached(io::task::fetch_directory_content);
+        return;
+    }
+
+    let displayed: Vec<&Entry> = directory_content
+        .0
+        .iter()
+        .filter(|entry| filters.matches(entry))
+        .collect();
+
+    if keys.just_pressed(KeyCode::Enter) {
+        match selected_entry.0.and_then(|index| displayed.get(index)) {
+            Some(Entry::Folder(name)) => location.path.push(name),
+            Some(Entry::Source(source_id)) => {
+                *location = AssetBrowserLocation::source_root(source_id.clone());
+            }
+            // Opening a file only records it in the recent assets
+            Some(entry @ Entry::File(_)) => {
+                if let Some(asset_path) = location.asset_path(entry) {
+                    recent_assets.push(asset_path);
+                }
+                return;
+            }
+            None => return,
+        }
+        commands.run_system_cached(io::task::fetch_directory_content);
+        return;
+    }
+
+    let cell_size = tile_layout.cell_size();
+    let content_width = content_node.size().x * content_node.inverse_scale_factor();
+    let columns = tile_layout.columns(content_width) as isize;
+    let offset = if keys.just_pressed(KeyCode::ArrowLeft) {
+        -1
+    } else if keys.just_pressed(KeyCode::ArrowRight) {
+        1
+    } else if keys.just_pressed(KeyCode::ArrowUp) {
+        -columns
+    } else if keys.just_pressed(KeyCode::ArrowDown) {
+        columns
+    } else {
+        return;
+    };
+    selected_entry.move_by(offset, displayed.len());
+
+    // Keep the selected tile on-screen
+    let Some(index) = selected_entry.0 else {
+        return;
+    };
+    if let Ok((mut scroll_box, scroll_box_node)) = scroll_box_query.get_mut(content_parent.parent())
+    {
+        let top = (index as isize / columns) as f32 * cell_size;
+        let view_height = scroll_box_node.size().y * scroll_box_node.inverse_scale_factor();
+        scroll_box.scroll_into_view(top, top + cell_size, view_height);
+    }
+}
+
+/// Highlight the tile of the [`SelectedEntry`], including tiles spawned once scrolled into view
+pub(crate) fn highlight_selected_entry(
+    selected_entry: Res<SelectedEntry>,
+    mut tile_query: Query<(Ref<EntryTile>, &mut BorderColor)>,
+) {
+    for (tile, mut border_color) in tile_query.iter_mut() {
+        if !selected_entry.is_changed() && !tile.is_added() {
+            continue;
+        }
+        *border_color = if selected_entry.0 == Some(tile.0) {
+            BorderColor::all(EditorColors::ACCENT_BLUE)
+        } else {
+            BorderColor::all(Color::NONE)
+        };
+    }
+}
@@ -1,28 +1,41 @@
 //! Module for all the UI components of the Asset Browser
 
-use bevy::prelude::*;
+use bevy::{prelude::*, ui::RelativeCursorPosition};
 use bevy_editor_styles::Theme;
 use bevy_pane_layout::prelude::*;
 
-use crate::{AssetBrowserLocation, DirectoryContent};
+use crate::{AssetBrowserLocation, BrowserBookmarks, DirectoryContent, ThumbnailSize};
 
+pub mod checkerboard;
+pub mod delete_confirmation;
+mod details;
 pub mod directory_content;
-mod nodes;
+pub mod drag;
+pub mod duplicates;
+pub(crate) mod keyboard_navigation;
+pub(crate) mod nodes;
+pub mod quick_look;
+pub(crate) mod rename;
+pub(crate) mod selection;
+pub mod thumbnail_size;
 pub mod top_bar;
 
 /// The root node for the asset browser.
+///
+/// Tracks the cursor to know which asset browser the keyboard shortcuts apply to.
 #[derive(Component)]
+#[require(RelativeCursorPosition)]
 pub struct AssetBrowserNode;
 
 /// Spawn [`AssetBrowserNode`] once the pane is created
-#[allow(clippy::too_many_arguments)]
 pub fn on_pane_creation(
     structure: In<PaneStructure>,
     mut commands: Commands,
     theme: Res<Theme>,
     location: Res<AssetBrowserLocation>,
-    asset_server: Res<AssetServer>,
-    directory_content: Res<DirectoryContent>,
+    thumbnail_size: Res<ThumbnailSize>,
+    bookmarks: Res<BrowserBookmarks>,
+    mut directory_content: ResMut<DirectoryContent>,
 ) {
     let asset_browser = commands
         .entity(structure.content)
@@ -35,15 +48,18 @@ pub fn on_pane_creation(
         })
         .id();
 
-    top_bar::spawn_top_bar(&mut commands, &theme, &location).insert(ChildOf(asset_browser));
-    directory_content::spawn_directory_content(
+    top_bar::spawn_top_bar(
         &mut commands,
-        &directory_content,
         &theme,
-        &asset_server,
         &location,
+        &bookmarks,
+        *thumbnail_size,
     )
     .insert(ChildOf(asset_browser));
+    directory_content::spawn_directory_content(&mut commands, &theme)
+        .insert(ChildOf(asset_browser));
+    // Populate the new pane with the current directory content
+    directory_content.set_changed();
 
     commands.entity(structure.root).insert(AssetBrowserNode);
 }
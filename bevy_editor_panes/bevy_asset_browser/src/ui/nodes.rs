@@ -1,57 +1,64 @@
 //! Contain function to spawn the different elements of the Asset Browser UI
 
-use atomicow::CowArc;
+use std::time::Duration;
+
 use bevy::{
-    asset::io::{AssetSource, AssetSourceBuilders, AssetSourceId},
-    feathers::cursor::EntityCursor,
-    prelude::*,
-    window::SystemCursorIcon,
+    asset::io::AssetSourceId, feathers::cursor::EntityCursor, prelude::*, window::SystemCursorIcon,
 };
 use bevy_context_menu::{ContextMenu, ContextMenuOption};
 use bevy_editor_styles::Theme;
 
-use crate::{AssetBrowserLocation, io, ui::source_id_to_string};
+use crate::{
+    AssetBrowserLocation, Entry, RecentAssets, SelectionMode, io, ui::source_id_to_string,
+};
 
+#[cfg(feature = "clipboard")]
+use super::directory_content::{copy_absolute_path, copy_asset_path};
 use super::{
-    DEFAULT_SOURCE_ID_NAME,
-    directory_content::{delete_file, delete_folder},
+    checkerboard::{CheckerboardImage, spawn_checkerboard},
+    directory_content::{TileIcons, delete_entries, reveal_in_file_manager},
+    quick_look::QuickLook,
+    rename::start_rename,
+    thumbnail_size::Thumbnail,
+    top_bar::PATH_SEGMENT_BACKGROUND_COLOR,
 };
 
+/// Longest delay between the two clicks of a double click
+const DOUBLE_CLICK_DELAY: Duration = Duration::from_millis(400);
+
+/// File extensions of the images that can be displayed as their own preview
+const IMAGE_EXTENSIONS: [&str; 12] = [
+    "png", "jpg", "jpeg", "bmp", "tga", "gif", "webp", "hdr", "exr", "ktx2", "dds", "qoi",
+];
+
+/// Check if a file is an image that can be loaded as its own preview
+pub(crate) fn is_image_file(file_name: &str) -> bool {
+    std::path::Path::new(file_name)
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| {
+            IMAGE_EXTENSIONS
+                .iter()
+                .any(|image_extension| extension.eq_ignore_ascii_case(image_extension))
+        })
+}
+
 pub(crate) fn spawn_source_node<'a>(
     commands: &'a mut Commands,
     source_id: &AssetSourceId,
     asset_server: &Res<AssetServer>,
     theme: &Res<Theme>,
 ) -> EntityCommands<'a> {
+    let clicked_source_id = source_id.clone_owned();
     let base_node = spawn_base_node(commands, theme)
         .observe(
             move |trigger: On<Pointer<Release>>,
                   mut commands: Commands,
-                  mut location: ResMut<AssetBrowserLocation>,
-                  mut asset_source_builder: ResMut<AssetSourceBuilders>,
-                  query_text: Query<&Text>,
-                  query_children: Query<&Children>| {
+                  mut location: ResMut<AssetBrowserLocation>| {
                 if trigger.event().button != PointerButton::Primary {
                     return;
                 }
-                let button = trigger.target();
-                let button_children = query_children.get(button).unwrap();
-                let source_name = &query_text
-                    .get(button_children[1])
-                    .expect("Child 1 of source node to have a text component")
-                    .0;
-                if source_name == DEFAULT_SOURCE_ID_NAME {
-                    location.source_id = Some(AssetSourceId::Default);
-                } else {
-                    location.source_id = asset_source_builder
-                        .build_sources(false, false)
-                        .iter()
-                        .find(|source| match source.id() {
-                            AssetSourceId::Name(CowArc::Static(name)) => name == source_name,
-                            _ => false,
-                        })
-                        .map(AssetSource::id);
-                }
+                *location = AssetBrowserLocation::source_root(clicked_source_id.clone());
                 commands.run_system_cached(io::task::fetch_directory_content);
             },
         )
@@ -60,6 +67,7 @@ pub(crate) fn spawn_source_node<'a>(
     // Icon
     commands.spawn((
         ImageNode::new(asset_server.load("embedded://bevy_asset_browser/assets/source_icon.png")),
+        Thumbnail,
         Node {
             height: Val::Px(50.0),
             ..default()
@@ -81,6 +89,10 @@ pub(crate) fn spawn_source_node<'a>(
     commands.entity(base_node)
 }
 
+/// Marker of the name label of a folder or file tile
+#[derive(Component)]
+pub(crate) struct TileLabel;
+
 pub(crate) fn spawn_folder_node<'a>(
     commands: &'a mut Commands,
     folder_name: String,
@@ -88,37 +100,28 @@ pub(crate) fn spawn_folder_node<'a>(
     location: &Res<AssetBrowserLocation>,
     theme: &Res<Theme>,
 ) -> EntityCommands<'a> {
+    let clicked_folder_name = folder_name.clone();
     let base_node = {
         let mut ec = spawn_base_node(commands, theme);
         ec.observe(
-            |trigger: On<Pointer<Release>>,
-             mut commands: Commands,
-             mut location: ResMut<AssetBrowserLocation>,
-             query_text: Query<&Text>,
-             query_children: Query<&Children>| {
-                if trigger.event().button != PointerButton::Primary {
+            move |trigger: On<Pointer<Release>>,
+                  mut commands: Commands,
+                  mut location: ResMut<AssetBrowserLocation>,
+                  keys: Res<ButtonInput<KeyCode>>| {
+                // Clicking with a modifier only selects the folder
+                if trigger.event().button != PointerButton::Primary
+                    || SelectionMode::from_keys(&keys) != SelectionMode::Replace
+                {
                     return;
                 }
-                let button = trigger.target();
-                let button_children = query_children.get(button).unwrap();
-                let folder_name = &query_text
-                    .get(button_children[1])
-                    .expect("Child 1 of folder node to have a text component")
-                    .0;
-                location.path.push(folder_name.clone());
+                location.path.push(&clicked_folder_name);
                 commands.run_system_cached(io::task::fetch_directory_content);
             },
         );
-        if location.source_id == Some(AssetSourceId::Default) {
-            ec.insert(ContextMenu::new([
-                // ContextMenuOption::new("Rename", |mut commands, entity| {
-                //     commands.run_system_cached_with(rename_asset, entity);
-                // }),
-                ContextMenuOption::new("Delete", |mut commands, entity| {
-                    commands.run_system_cached_with(delete_folder, entity);
-                }),
-            ]));
-        }
+        ec.insert(ContextMenu::new(entry_context_menu_options(
+            location,
+            Entry::Folder(folder_name.clone()),
+        )));
         ec.id()
     };
 
@@ -127,6 +130,7 @@ pub(crate) fn spawn_folder_node<'a>(
         ImageNode::new(
             asset_server.load("embedded://bevy_asset_browser/assets/directory_icon.png"),
         ),
+        Thumbnail,
         Node {
             height: Val::Px(50.0),
             ..default()
@@ -135,6 +139,7 @@ pub(crate) fn spawn_folder_node<'a>(
     ));
     // Folder Name
     commands.spawn((
+        TileLabel,
         Text::new(folder_name),
         TextFont {
             font: theme.text.font.clone(),
@@ -154,39 +159,68 @@ pub(crate) fn spawn_file_node<'a>(
     asset_server: &Res<AssetServer>,
     location: &Res<AssetBrowserLocation>,
     theme: &Res<Theme>,
+    checkerboard: Option<&CheckerboardImage>,
+    show_extension_badge: bool,
+    tile_icons: &TileIcons,
 ) -> EntityCommands<'a> {
     let base_node = {
         let mut ec = spawn_base_node(commands, theme);
-        if location.source_id == Some(AssetSourceId::Default) {
-            ec.insert(ContextMenu::new([
-                // ContextMenuOption::new("Rename", |mut commands, entity| {
-                //     commands.run_system_cached_with(rename_asset, entity);
-                // }),
-                ContextMenuOption::new("Delete", |mut commands, entity| {
-                    commands.run_system_cached_with(delete_file, entity);
-                }),
-                // TODO: add this to the folders as well
-                // TODO: fix this, doesn't yet work, it opens the file instead of revealing it in the file manager (at least on linux)
-                // ContextMenuOption::new("Reveal in File Manager", |mut commands, entity| {
-                //     commands.run_system_cached_with(reveal_in_file_manager, entity);
-                // }),
-            ]));
-        }
+        ec.insert(ContextMenu::new(entry_context_menu_options(
+            location,
+            Entry::File(file_name.clone()),
+        )));
         ec.id()
     };
 
-    // Icon
-    commands.spawn((
-        ImageNode::new(asset_server.load("embedded://bevy_asset_browser/assets/file_icon.png")),
-        Node {
-            height: Val::Px(50.0),
-            ..default()
+    let asset_path = location.asset_path(&Entry::File(file_name.clone()));
+    // Images are their own thumbnail, displayed in front of the checkerboard so their transparency shows
+    let icon = match asset_path.clone().filter(|_| is_image_file(&file_name)) {
+        Some(image_path) => asset_server.load(image_path),
+        None => match tile_icons.icon(&file_name) {
+            Some(icon) => icon.clone(),
+            None => asset_server.load("embedded://bevy_asset_browser/assets/file_icon.png"),
         },
-        ChildOf(base_node),
-    ));
-    // Folder Name
+    };
+    // Quick look, enlarging the thumbnail
+    let quick_look_icon = icon.clone();
+    commands.entity(base_node).observe(
+        move |trigger: On<Pointer<Press>>, time: Res<Time>, mut quick_look: ResMut<QuickLook>| {
+            if trigger.event().button != PointerButton::Primary {
+                return;
+            }
+            quick_look.start(
+                quick_look_icon.clone(),
+                trigger.pointer_location.position,
+                time.elapsed(),
+            );
+        },
+    );
+    // Opening a file only records it in the recent assets
+    commands.entity(base_node).observe(
+        move |trigger: On<Pointer<Click>>,
+              time: Res<Time>,
+              mut last_click: Local<Option<Duration>>,
+              mut recent_assets: ResMut<RecentAssets>| {
+            if trigger.event().button != PointerButton::Primary {
+                return;
+            }
+            let now = time.elapsed();
+            match last_click.take() {
+                Some(last_click) if now - last_click <= DOUBLE_CLICK_DELAY => {
+                    if let Some(asset_path) = &asset_path {
+                        recent_assets.push(asset_path.clone());
+                    }
+                }
+                _ => *last_click = Some(now),
+            }
+        },
+    );
+
+    spawn_file_icon(commands, base_node, &file_name, icon, checkerboard);
+    // File Name
     commands.spawn((
-        Text::new(file_name),
+        TileLabel,
+        Text::new(file_name.clone()),
         TextFont {
             font: theme.text.font.clone(),
             font_size: 10.0,
@@ -195,10 +229,141 @@ pub(crate) fn spawn_file_node<'a>(
         TextColor(theme.text.text_color),
         ChildOf(base_node),
     ));
+    if show_extension_badge {
+        spawn_extension_badge(
+            commands,
+            base_node,
+            &file_name,
+            TextFont {
+                font: theme.text.font.clone(),
+                font_size: 8.0,
+                ..default()
+            },
+            theme.text.low_priority,
+        );
+    }
 
     commands.entity(base_node)
 }
 
+/// Get the label of the extension badge of a file, `None` for files without extension
+pub(crate) fn extension_badge(file_name: &str) -> Option<String> {
+    std::path::Path::new(file_name)
+        .extension()
+        .map(|extension| extension.to_string_lossy().to_uppercase())
+}
+
+/// Marker of the badge displaying the extension of a file in the corner of its tile
+#[derive(Component)]
+pub struct ExtensionBadge;
+
+/// Spawn the extension badge in the top right corner of a file tile, if the file has an extension
+fn spawn_extension_badge(
+    commands: &mut Commands,
+    tile: Entity,
+    file_name: &str,
+    text_font: TextFont,
+    text_color: Color,
+) {
+    let Some(extension) = extension_badge(file_name) else {
+        return;
+    };
+    let badge = commands
+        .spawn((
+            ExtensionBadge,
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Px(2.0),
+                right: Val::Px(2.0),
+                padding: UiRect::horizontal(Val::Px(3.0)),
+                ..default()
+            },
+            BackgroundColor(PATH_SEGMENT_BACKGROUND_COLOR),
+            BorderRadius::all(Val::Px(3.0)),
+            Pickable::IGNORE,
+            ChildOf(tile),
+        ))
+        .id();
+    commands.spawn((
+        Text::new(extension),
+        text_font,
+        TextColor(text_color),
+        Pickable::IGNORE,
+        ChildOf(badge),
+    ));
+}
+
+/// Build the context menu options of a folder or file tile
+///
+/// Entries can only be modified inside the Default source, but their paths can always be copied.
+fn entry_context_menu_options(
+    location: &AssetBrowserLocation,
+    entry: Entry,
+) -> Vec<ContextMenuOption> {
+    let mut options = Vec::new();
+    if location.source_id == Some(AssetSourceId::Default) {
+        options.extend([
+            ContextMenuOption::new("Rename", |mut commands, entity| {
+                commands.run_system_cached_with(start_rename, entity);
+            }),
+            ContextMenuOption::new("Delete", |mut commands, entity| {
+                commands.run_system_cached_with(delete_entries, entity);
+            }),
+        ]);
+        let reveal_entry = entry.clone();
+        options.push(ContextMenuOption::new(
+            "Reveal in File Manager",
+            move |mut commands, _| {
+                commands.run_system_cached_with(reveal_in_file_manager, reveal_entry.clone());
+            },
+        ));
+        #[cfg(feature = "clipboard")]
+        let absolute_path_entry = entry.clone();
+        #[cfg(feature = "clipboard")]
+        options.push(ContextMenuOption::new(
+            "Copy Absolute Path",
+            move |mut commands, _| {
+                commands.run_system_cached_with(copy_absolute_path, absolute_path_entry.clone());
+            },
+        ));
+    }
+    #[cfg(feature = "clipboard")]
+    options.push(ContextMenuOption::new(
+        "Copy Asset Path",
+        move |mut commands, _| {
+            commands.run_system_cached_with(copy_asset_path, entry.clone());
+        },
+    ));
+    options
+}
+
+/// Spawn the icon of a file tile, in front of a checkerboard for images if a `checkerboard` is given
+fn spawn_file_icon(
+    commands: &mut Commands,
+    tile: Entity,
+    file_name: &str,
+    icon: Handle<Image>,
+    checkerboard: Option<&CheckerboardImage>,
+) {
+    let icon_parent = match checkerboard {
+        Some(checkerboard) if is_image_file(file_name) => {
+            spawn_checkerboard(commands, checkerboard)
+                .insert(ChildOf(tile))
+                .id()
+        }
+        _ => tile,
+    };
+    commands.spawn((
+        ImageNode::new(icon),
+        Thumbnail,
+        Node {
+            height: Val::Px(50.0),
+            ..default()
+        },
+        ChildOf(icon_parent),
+    ));
+}
+
 fn spawn_base_node<'a>(commands: &'a mut Commands, theme: &Res<Theme>) -> EntityCommands<'a> {
     commands.spawn((
         Button,
@@ -218,3 +383,96 @@ fn spawn_base_node<'a>(commands: &'a mut Commands, theme: &Res<Theme>) -> Entity
         EntityCursor::System(SystemCursorIcon::Pointer),
     ))
 }
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::world::CommandQueue;
+
+    use super::*;
+    use crate::ui::checkerboard::Checkerboard;
+
+    #[test]
+    fn extension_badges_are_uppercased() {
+        let mut world = World::new();
+        let mut queue = CommandQueue::default();
+        let mut commands = Commands::new(&mut queue, &world);
+        let tiles = ["player.png", "level.glb", "Makefile", ".gitignore"].map(|file_name| {
+            let tile = commands.spawn_empty().id();
+            spawn_extension_badge(
+                &mut commands,
+                tile,
+                file_name,
+                TextFont::default(),
+                Color::WHITE,
+            );
+            tile
+        });
+        queue.apply(&mut world);
+
+        let badge_label = |world: &World, tile: Entity| {
+            let badges: Vec<Entity> = world
+                .get::<Children>(tile)
+                .into_iter()
+                .flatten()
+                .copied()
+                .filter(|child| world.get::<ExtensionBadge>(*child).is_some())
+                .collect();
+            badges.first().map(|badge| {
+                let label = world.get::<Children>(*badge).unwrap()[0];
+                world.get::<Text>(label).unwrap().0.clone()
+            })
+        };
+        assert_eq!(badge_label(&world, tiles[0]).as_deref(), Some("PNG"));
+        assert_eq!(badge_label(&world, tiles[1]).as_deref(), Some("GLB"));
+        assert_eq!(badge_label(&world, tiles[2]), None);
+        assert_eq!(badge_label(&world, tiles[3]), None);
+    }
+
+    #[test]
+    fn checkerboard_is_behind_image_icons() {
+        let mut world = World::new();
+        let checkerboard = CheckerboardImage(Handle::default());
+        let mut queue = CommandQueue::default();
+        let mut commands = Commands::new(&mut queue, &world);
+        let image_tile = commands.spawn_empty().id();
+        let text_tile = commands.spawn_empty().id();
+        let disabled_tile = commands.spawn_empty().id();
+        spawn_file_icon(
+            &mut commands,
+            image_tile,
+            "sprite.PNG",
+            Handle::default(),
+            Some(&checkerboard),
+        );
+        spawn_file_icon(
+            &mut commands,
+            text_tile,
+            "notes.txt",
+            Handle::default(),
+            Some(&checkerboard),
+        );
+        spawn_file_icon(
+            &mut commands,
+            disabled_tile,
+            "sprite.png",
+            Handle::default(),
+            None,
+        );
+        queue.apply(&mut world);
+
+        let mut checkerboards = world.query_filtered::<(&ChildOf, &Children), With<Checkerboard>>();
+        let checkerboards: Vec<_> = checkerboards.iter(&world).collect();
+        assert_eq!(checkerboards.len(), 1);
+        let (parent, children) = checkerboards[0];
+        assert_eq!(parent.parent(), image_tile);
+        // The checkered background is spawned before the icon so it's rendered behind it
+        assert_eq!(children.len(), 2);
+        assert!(world.get::<Node>(children[0]).unwrap().position_type == PositionType::Absolute);
+        assert!(world.get::<ImageNode>(children[1]).is_some());
+        for tile in [text_tile, disabled_tile] {
+            let icon = world.get::<Children>(tile).unwrap()[0];
+            assert!(world.get::<ImageNode>(icon).is_some());
+            assert!(world.get::<Checkerboard>(icon).is_none());
+        }
+    }
+}
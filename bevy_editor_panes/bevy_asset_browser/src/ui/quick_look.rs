@@ -0,0 +1,164 @@
+//! Press and hold a file tile to display an enlarged floating preview of it
+
+use std::time::Duration;
+
+use bevy::prelude::*;
+
+use super::checkerboard::{TileBackground, spawn_checkerboard};
+
+/// How long a file tile has to be held pressed before its quick look appears
+pub const QUICK_LOOK_DELAY: Duration = Duration::from_millis(300);
+
+/// Size of the quick look floating preview
+const QUICK_LOOK_SIZE: f32 = 256.0;
+
+/// Offset between the cursor and the quick look floating preview
+const QUICK_LOOK_CURSOR_OFFSET: f32 = 12.0;
+
+/// State of the quick look, tracking the file tile currently held pressed
+#[derive(Resource, Default)]
+pub struct QuickLook {
+    pending: Option<QuickLookRequest>,
+    node: Option<Entity>,
+}
+
+/// A file tile pressed by the user, waiting for [`QUICK_LOOK_DELAY`] to display its quick look
+struct QuickLookRequest {
+    image: Handle<Image>,
+    position: Vec2,
+    pressed_at: Duration,
+}
+
+impl QuickLook {
+    /// Start waiting for [`QUICK_LOOK_DELAY`] to display `image` next to the pressed `position`
+    pub(crate) fn start(&mut self, image: Handle<Image>, position: Vec2, now: Duration) {
+        self.pending = Some(QuickLookRequest {
+            image,
+            position,
+            pressed_at: now,
+        });
+    }
+
+    /// Stop waiting for the quick look and remove it if displayed
+    pub(crate) fn cancel(&mut self, commands: &mut Commands) {
+        if let Some(node) = self.node.take() {
+            commands.entity(node).despawn();
+        }
+        self.pending = None;
+    }
+}
+
+/// The floating node displaying the quick look preview
+#[derive(Component)]
+pub struct QuickLookNode;
+
+/// Check if a file tile is held pressed or its quick look is displayed
+pub(crate) fn quick_look_is_active(quick_look: Res<QuickLook>) -> bool {
+    quick_look.pending.is_some() || quick_look.node.is_some()
+}
+
+/// Display the quick look once the tile has been held long enough, and remove it when released
+pub(crate) fn update_quick_look(
+    mut commands: Commands,
+    time: Res<Time>,
+    mouse_input: Res<ButtonInput<MouseButton>>,
+    tile_background: TileBackground,
+    mut quick_look: ResMut<QuickLook>,
+) {
+    if !mouse_input.pressed(MouseButton::Left) {
+        quick_look.cancel(&mut commands);
+        return;
+    }
+    if quick_look.node.is_some() {
+        return;
+    }
+    let Some(request) = &quick_look.pending else {
+        return;
+    };
+    if time.elapsed().saturating_sub(request.pressed_at) < QUICK_LOOK_DELAY {
+        return;
+    }
+    let mut node = match tile_background.checkerboard() {
+        Some(checkerboard) => spawn_checkerboard(&mut commands, checkerboard),
+        None => commands.spawn_empty(),
+    };
+    node.insert((
+        QuickLookNode,
+        Node {
+            position_type: PositionType::Absolute,
+            left: Val::Px(request.position.x + QUICK_LOOK_CURSOR_OFFSET),
+            top: Val::Px(request.position.y + QUICK_LOOK_CURSOR_OFFSET),
+            width: Val::Px(QUICK_LOOK_SIZE),
+            height: Val::Px(QUICK_LOOK_SIZE),
+            ..default()
+        },
+        GlobalZIndex(i32::MAX - 1),
+        Pickable::IGNORE,
+    ));
+    let node = node.id();
+    commands.spawn((
+        ImageNode::new(request.image.clone()),
+        Node {
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            ..default()
+        },
+        Pickable::IGNORE,
+        ChildOf(node),
+    ));
+    quick_look.node = Some(node);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CheckerboardBackground;
+
+    fn quick_look_nodes(app: &mut App) -> usize {
+        app.world_mut()
+            .query_filtered::<(), With<QuickLookNode>>()
+            .iter(app.world())
+            .count()
+    }
+
+    #[test]
+    fn quick_look_is_displayed_while_held() {
+        let mut app = App::new();
+        app.init_resource::<Time>()
+            .init_resource::<CheckerboardBackground>()
+            .init_resource::<ButtonInput<MouseButton>>()
+            .init_resource::<QuickLook>()
+            .add_systems(Update, update_quick_look);
+
+        app.world_mut().resource_mut::<QuickLook>().start(
+            Handle::default(),
+            Vec2::new(20.0, 40.0),
+            Duration::ZERO,
+        );
+        app.world_mut()
+            .resource_mut::<ButtonInput<MouseButton>>()
+            .press(MouseButton::Left);
+
+        // Not held long enough yet
+        app.update();
+        assert_eq!(quick_look_nodes(&mut app), 0);
+
+        app.world_mut()
+            .resource_mut::<Time>()
+            .advance_by(QUICK_LOOK_DELAY);
+        app.update();
+        assert_eq!(quick_look_nodes(&mut app), 1);
+
+        // Stays displayed while held
+        app.update();
+        assert_eq!(quick_look_nodes(&mut app), 1);
+
+        app.world_mut()
+            .resource_mut::<ButtonInput<MouseButton>>()
+            .release(MouseButton::Left);
+        app.update();
+        assert_eq!(quick_look_nodes(&mut app), 0);
+        let quick_look = app.world().resource::<QuickLook>();
+        assert!(quick_look.pending.is_none() && quick_look.node.is_none());
+    }
+}
@@ -0,0 +1,187 @@
+//! Rename files and folders in place, from the tiles context menu
+
+use bevy::{
+    input::keyboard::KeyboardInput,
+    input_focus::{FocusedInput, InputFocus},
+    prelude::*,
+};
+use bevy_editor_styles::Theme;
+use bevy_text_editing::{CharPosition, EditableTextLine, SetCursorPosition};
+
+use crate::{AssetBrowserLocation, DefaultSourceFilePath, io};
+
+use super::{nodes::TileLabel, top_bar::PATH_SEGMENT_BACKGROUND_COLOR};
+
+/// The text field replacing the label of a tile being renamed
+#[derive(Component)]
+pub(crate) struct RenameField {
+    /// The hidden label of the tile, displayed again once the rename is done
+    label: Entity,
+    /// The name of the entry before renaming it
+    original_name: String,
+}
+
+/// Replace the label of a tile with a text field to rename its entry
+///
+/// Enter applies the new name, Escape or moving the [`InputFocus`] away cancels the rename.
+pub(crate) fn start_rename(
+    tile: In<Entity>,
+    mut commands: Commands,
+    theme: Res<Theme>,
+    query_children: Query<&Children>,
+    query_label: Query<&Text, With<TileLabel>>,
+    mut query_node: Query<&mut Node>,
+    input_focus: Option<ResMut<InputFocus>>,
+) {
+    let Some((label, name)) = query_children.get(*tile).ok().and_then(|children| {
+        children
+            .iter()
+            .find_map(|child| Some((child, query_label.get(child).ok()?.0.clone())))
+    }) else {
+        return;
+    };
+    if let Ok(mut label_node) = query_node.get_mut(label) {
+        label_node.display = Display::None;
+    }
+    let field = commands
+        .spawn((
+            RenameField {
+                label,
+                original_name: name.clone(),
+            },
+            EditableTextLine::new(name.clone()),
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Px(14.0),
+                ..default()
+            },
+            TextFont {
+                font: theme.text.font.clone(),
+                font_size: 10.0,
+                ..default()
+            },
+            BackgroundColor(PATH_SEGMENT_BACKGROUND_COLOR),
+            theme.general.border_radius,
+            ChildOf(*tile),
+        ))
+        .observe(on_rename_key_input)
+        .id();
+    if let Some(mut input_focus) = input_focus {
+        input_focus.0 = Some(field);
+    }
+    commands.trigger_targets(SetCursorPosition(CharPosition(name.chars().count())), field);
+}
+
+/// Apply the new name on Enter, or cancel the rename on Escape
+///
+/// On failure, like a name collision or invalid characters, the error is reported and the old name is kept.
+fn on_rename_key_input(
+    trigger: On<FocusedInput<KeyboardInput>>,
+    mut commands: Commands,
+    query_field: Query<(&RenameField, &EditableTextLine)>,
+    mut query_node: Query<&mut Node>,
+    default_source_file_path: Res<DefaultSourceFilePath>,
+    location: Res<AssetBrowserLocation>,
+    input_focus: Option<ResMut<InputFocus>>,
+) {
+    let input = &trigger.event().input;
+    if !input.state.is_pressed() {
+        return;
+    }
+    let field_entity = trigger.target();
+    let Ok((field, text_line)) = query_field.get(field_entity) else {
+        return;
+    };
+    match input.key_code {
+        KeyCode::Enter | KeyCode::NumpadEnter => {
+            let mut path = default_source_file_path.0.clone();
+            path.push(location.path.as_path());
+            path.push(&field.original_name);
+            match io::rename_entry(path, &text_line.text) {
+                Ok(_) => commands.run_system_cached(io::task::fetch_directory_content),
+                Err(e) => eprintln!("Failed to rename {}: {e}", field.original_name),
+            }
+        }
+        KeyCode::Escape => {}
+        _ => return,
+    }
+    end_rename(&mut commands, field_entity, field, &mut query_node);
+    if let Some(mut input_focus) = input_focus {
+        input_focus.0 = None;
+    }
+}
+
+/// Cancel the renames whose field lost the [`InputFocus`], like when clicking elsewhere
+pub(crate) fn cancel_unfocused_rename(
+    mut commands: Commands,
+    input_focus: Res<InputFocus>,
+    query_field: Query<(Entity, &RenameField)>,
+    mut query_node: Query<&mut Node>,
+) {
+    for (field_entity, field) in query_field.iter() {
+        if input_focus.0 != Some(field_entity) {
+            end_rename(&mut commands, field_entity, field, &mut query_node);
+        }
+    }
+}
+
+/// Remove the rename field and display the label of the tile again
+///
+/// The tiles are regenerated once the renamed entry is fetched.
+fn end_rename(
+    commands: &mut Commands,
+    field_entity: Entity,
+    field: &RenameField,
+    query_node: &mut Query<&mut Node>,
+) {
+    if let Ok(mut label_node) = query_node.get_mut(field.label) {
+        label_node.display = Display::default();
+    }
+    commands.entity(field_entity).despawn();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn losing_focus_cancels_the_rename() {
+        let mut world = World::new();
+        let tile = world.spawn_empty().id();
+        let label = world
+            .spawn((
+                TileLabel,
+                Text::new("player.png"),
+                Node {
+                    display: Display::None,
+                    ..default()
+                },
+                ChildOf(tile),
+            ))
+            .id();
+        let field = world
+            .spawn((
+                RenameField {
+                    label,
+                    original_name: "player.png".to_string(),
+                },
+                ChildOf(tile),
+            ))
+            .id();
+        world.insert_resource(InputFocus(Some(field)));
+
+        world.run_system_cached(cancel_unfocused_rename).unwrap();
+        assert!(
+            world.get_entity(field).is_ok(),
+            "the field still has the focus"
+        );
+
+        world.resource_mut::<InputFocus>().0 = None;
+        world.run_system_cached(cancel_unfocused_rename).unwrap();
+        assert!(world.get_entity(field).is_err());
+        assert_eq!(
+            world.get::<Node>(label).unwrap().display,
+            Display::default()
+        );
+    }
+}
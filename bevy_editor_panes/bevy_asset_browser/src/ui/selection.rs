@@ -0,0 +1,83 @@
+//! Select one or multiple [entries](Entry) with the mouse, see [`Selection`]
+
+use std::path::PathBuf;
+
+use bevy::prelude::*;
+use bevy_editor_styles::colors::EditorColors;
+
+use crate::{
+    AssetBrowserLocation, DirectoryContent, Entry, Selection, SelectionMode,
+    ui::{directory_content::DirectoryContentFilters, keyboard_navigation::EntryTile},
+};
+
+/// Path of the [`Entry`] displayed by a tile, relative to the current asset source root
+#[derive(Component)]
+pub(crate) struct EntryPath(pub(crate) PathBuf);
+
+/// Index of the last entry clicked without Shift, where [`SelectionMode::Range`] starts
+#[derive(Resource, Default)]
+pub(crate) struct SelectionAnchor(Option<usize>);
+
+/// Update the [`Selection`] when a tile is clicked, with respect to the held modifier keys
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn select_entry(
+    trigger: On<Pointer<Release>>,
+    keys: Res<ButtonInput<KeyCode>>,
+    location: Res<AssetBrowserLocation>,
+    directory_content: Res<DirectoryContent>,
+    filters: DirectoryContentFilters,
+    tile_query: Query<&EntryTile>,
+    mut selection: ResMut<Selection>,
+    mut anchor: ResMut<SelectionAnchor>,
+) {
+    if trigger.event().button != PointerButton::Primary {
+        return;
+    }
+    let Ok(tile) = tile_query.get(trigger.target()) else {
+        return;
+    };
+    let displayed: Vec<PathBuf> = directory_content
+        .0
+        .iter()
+        .filter(|entry| filters.matches(entry))
+        .filter_map(|entry| match entry {
+            Entry::Folder(name) | Entry::File(name) => Some(location.path.join(name)),
+            Entry::Source(_) => None,
+        })
+        .collect();
+    anchor.0 = selection.click(
+        SelectionMode::from_keys(&keys),
+        tile.0,
+        anchor.0,
+        &displayed,
+    );
+}
+
+/// Clear the [`Selection`], the selected entries are no longer displayed once the location changes
+pub(crate) fn clear_selection(
+    mut selection: ResMut<Selection>,
+    mut anchor: ResMut<SelectionAnchor>,
+) {
+    selection.0.clear();
+    anchor.0 = None;
+}
+
+/// Highlight the tiles of the selected entries
+pub(crate) fn highlight_selection(
+    mut commands: Commands,
+    selection: Res<Selection>,
+    tile_query: Query<(Entity, Ref<EntryPath>)>,
+) {
+    for (tile, path) in tile_query.iter() {
+        if !selection.is_changed() && !path.is_added() {
+            continue;
+        }
+        commands
+            .entity(tile)
+            .insert(BackgroundColor(if selection.0.contains(&path.0) {
+                EditorColors::ACCENT_BLUE.with_alpha(0.3)
+            } else {
+                Color::NONE
+            }));
+    }
+}
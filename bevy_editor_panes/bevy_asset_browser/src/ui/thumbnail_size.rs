@@ -0,0 +1,129 @@
+//! Slider of the top bar resizing the tiles thumbnails, see [`ThumbnailSize`]
+
+use std::fmt::Debug;
+
+use bevy::{
+    feathers::cursor::EntityCursor, prelude::*, ui::RelativeCursorPosition,
+    window::SystemCursorIcon,
+};
+use bevy_editor_styles::Theme;
+
+use crate::{BrowserViewMode, ThumbnailSize};
+
+use super::{
+    details::DETAILS_ICON_SIZE, keyboard_navigation::EntryTile,
+    top_bar::PATH_SEGMENT_BACKGROUND_COLOR,
+};
+
+/// Width in pixels of the slider thumb
+const SLIDER_THUMB_WIDTH: f32 = 6.0;
+
+/// Marker of the icons and previews displayed at the [`ThumbnailSize`]
+#[derive(Component)]
+pub(crate) struct Thumbnail;
+
+/// The track of the [`ThumbnailSize`] slider, clicking or dragging on it picks the size under the cursor
+#[derive(Component)]
+pub struct ThumbnailSizeSlider;
+
+/// The thumb of the [`ThumbnailSizeSlider`], placed at the current [`ThumbnailSize`]
+#[derive(Component)]
+pub struct ThumbnailSizeSliderThumb;
+
+/// Spawn the [`ThumbnailSizeSlider`] with its thumb
+pub(crate) fn spawn_thumbnail_size_slider<'a>(
+    commands: &'a mut Commands,
+    theme: &Theme,
+    thumbnail_size: ThumbnailSize,
+) -> EntityCommands<'a> {
+    let slider = commands
+        .spawn((
+            ThumbnailSizeSlider,
+            Node {
+                width: Val::Px(80.0),
+                height: Val::Px(4.0),
+                margin: UiRect::horizontal(Val::Px(10.0)),
+                ..default()
+            },
+            BackgroundColor(PATH_SEGMENT_BACKGROUND_COLOR),
+            theme.general.border_radius,
+            RelativeCursorPosition::default(),
+            EntityCursor::System(SystemCursorIcon::Pointer),
+        ))
+        .observe(pick_thumbnail_size::<Press>)
+        .observe(pick_thumbnail_size::<Drag>)
+        .id();
+    commands.spawn((
+        ThumbnailSizeSliderThumb,
+        Node {
+            position_type: PositionType::Absolute,
+            left: Val::Percent(thumbnail_size.slider_fraction() * 100.0),
+            top: Val::Px(-4.0),
+            width: Val::Px(SLIDER_THUMB_WIDTH),
+            height: Val::Px(12.0),
+            margin: UiRect::left(Val::Px(-SLIDER_THUMB_WIDTH / 2.0)),
+            ..default()
+        },
+        BackgroundColor(theme.text.text_color),
+        theme.general.border_radius,
+        Pickable::IGNORE,
+        ChildOf(slider),
+    ));
+    commands.entity(slider)
+}
+
+/// Set the [`ThumbnailSize`] to the size under the cursor when pressing or dragging the slider
+fn pick_thumbnail_size<E: Debug + Clone + Reflect>(
+    trigger: On<Pointer<E>>,
+    query: Query<&RelativeCursorPosition>,
+    mut thumbnail_size: ResMut<ThumbnailSize>,
+) {
+    let Some(cursor) = query
+        .get(trigger.target())
+        .ok()
+        .and_then(|cursor| cursor.normalized)
+    else {
+        return;
+    };
+    // The cursor position is relative to the center of the slider
+    thumbnail_size.set_if_neq(ThumbnailSize::from_slider(cursor.x + 0.5));
+}
+
+/// Move the [`ThumbnailSizeSliderThumb`] to the current [`ThumbnailSize`]
+pub(crate) fn update_slider_thumb(
+    thumbnail_size: Res<ThumbnailSize>,
+    mut thumb_query: Query<&mut Node, With<ThumbnailSizeSliderThumb>>,
+) {
+    for mut node in thumb_query.iter_mut() {
+        node.left = Val::Percent(thumbnail_size.slider_fraction() * 100.0);
+    }
+}
+
+/// Resize the tiles and their [thumbnails](Thumbnail) when spawned or when the [`ThumbnailSize`] changes
+///
+/// The rows of the [`BrowserViewMode::Details`] view keep their size, only their icons are shrunk.
+pub(crate) fn resize_tiles(
+    thumbnail_size: Res<ThumbnailSize>,
+    view_mode: Res<BrowserViewMode>,
+    mut tile_query: Query<(Ref<EntryTile>, &mut Node), Without<Thumbnail>>,
+    mut thumbnail_query: Query<(Ref<Thumbnail>, &mut Node), Without<EntryTile>>,
+) {
+    let is_grid = *view_mode == BrowserViewMode::Grid;
+    let tile_size = Val::Px(thumbnail_size.tile_size());
+    for (tile, mut node) in tile_query.iter_mut() {
+        if is_grid && (thumbnail_size.is_changed() || tile.is_added()) {
+            node.width = tile_size;
+            node.height = tile_size;
+        }
+    }
+    let thumbnail_height = Val::Px(if is_grid {
+        thumbnail_size.0 as f32
+    } else {
+        DETAILS_ICON_SIZE
+    });
+    for (thumbnail, mut node) in thumbnail_query.iter_mut() {
+        if thumbnail_size.is_changed() || thumbnail.is_added() {
+            node.height = thumbnail_height;
+        }
+    }
+}
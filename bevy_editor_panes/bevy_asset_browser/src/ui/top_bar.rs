@@ -1,9 +1,20 @@
-use bevy::{feathers::cursor::EntityCursor, prelude::*, window::SystemCursorIcon};
-use bevy_editor_styles::Theme;
+use bevy::{
+    feathers::cursor::EntityCursor,
+    input_focus::InputFocus,
+    prelude::*,
+    ui::{InteractionDisabled, RelativeCursorPosition},
+    window::SystemCursorIcon,
+};
+use bevy_context_menu::{ContextMenu, ContextMenuOption};
+use bevy_editor_styles::{Theme, colors::EditorColors};
+use bevy_text_editing::{CharPosition, EditableTextLine, SetCursorPosition, SetText, TextChanged};
 
-use crate::{AssetBrowserLocation, io};
+use crate::{
+    AssetBrowserLocation, BrowserBookmarks, BrowserViewMode, DirectoryFilter, LocationHistory,
+    ShowHiddenFiles, ThumbnailSize, io,
+};
 
-use super::source_id_to_string;
+use super::{AssetBrowserNode, source_id_to_string, thumbnail_size::spawn_thumbnail_size_slider};
 
 /// Color of the path segment background when idle
 pub const PATH_SEGMENT_BACKGROUND_COLOR: Color = Color::srgb(0.2, 0.2, 0.2);
@@ -12,11 +23,53 @@ pub const PATH_SEGMENT_BACKGROUND_COLOR: Color = Color::srgb(0.2, 0.2, 0.2);
 #[derive(Component)]
 pub struct TopBarNode;
 
+/// The node containing the path segments of the current [`AssetBrowserLocation`]
+#[derive(Component)]
+pub struct LocationPathNode;
+
+/// The text field used to filter the directory content by name, see [`DirectoryFilter`]
+#[derive(Component)]
+pub struct SearchField;
+
+/// The button navigating to the parent directory of the current [`AssetBrowserLocation`]
+#[derive(Component)]
+pub struct ParentDirectoryButton;
+
+/// The button fetching the content of the current [`AssetBrowserLocation`] again, to pick up external changes
+#[derive(Component)]
+pub struct RefreshButton;
+
+/// The button going back to the previous location of the [`LocationHistory`]
+#[derive(Component)]
+pub struct BackButton;
+
+/// The button going forward to the next location of the [`LocationHistory`]
+#[derive(Component)]
+pub struct ForwardButton;
+
+/// The strip of buttons navigating to the [`BrowserBookmarks`]
+#[derive(Component)]
+pub struct BookmarksNode;
+
+/// The button navigating to the bookmark at this index of the [`BrowserBookmarks`]
+#[derive(Component)]
+pub struct BookmarkButton(pub usize);
+
+/// The button toggling [`ShowHiddenFiles`]
+#[derive(Component)]
+pub struct ShowHiddenFilesToggle;
+
+/// The button switching the [`BrowserViewMode`] between the grid and the details view
+#[derive(Component)]
+pub struct ViewModeToggle;
+
 /// Spawn the top bar of the asset browser
 pub(crate) fn spawn_top_bar<'a>(
     commands: &'a mut Commands,
     theme: &Res<Theme>,
     location: &Res<AssetBrowserLocation>,
+    bookmarks: &BrowserBookmarks,
+    thumbnail_size: ThumbnailSize,
 ) -> EntityCommands<'a> {
     let top_bar = commands
         .spawn((
@@ -32,7 +85,47 @@ pub(crate) fn spawn_top_bar<'a>(
             theme.pane.header_background_color,
         ))
         .id();
+    spawn_top_bar_button(commands, theme.as_ref(), "\u{2190}")
+        .insert((BackButton, InteractionDisabled, ChildOf(top_bar)))
+        .observe(navigate_back);
+    spawn_top_bar_button(commands, theme.as_ref(), "\u{2192}")
+        .insert((ForwardButton, InteractionDisabled, ChildOf(top_bar)))
+        .observe(navigate_forward);
+    let parent_directory_button = spawn_top_bar_button(commands, theme.as_ref(), "\u{2191}")
+        .insert((ParentDirectoryButton, ChildOf(top_bar)))
+        .observe(navigate_to_parent_directory)
+        .id();
+    // Kept up to date by `refresh_parent_directory_button` once the location changes
+    if !has_parent_directory(location) {
+        commands
+            .entity(parent_directory_button)
+            .insert(InteractionDisabled);
+    }
+    spawn_top_bar_button(commands, theme.as_ref(), "\u{21bb}")
+        .insert((RefreshButton, ChildOf(top_bar)))
+        .observe(refresh_directory_content);
     spawn_location_path_ui(commands, theme, location).insert(ChildOf(top_bar));
+    let bookmarks_node = commands
+        .spawn((
+            BookmarksNode,
+            Node {
+                flex_direction: FlexDirection::Row,
+                align_items: AlignItems::Center,
+                margin: UiRect::left(Val::Px(10.0)),
+                ..default()
+            },
+            ChildOf(top_bar),
+        ))
+        .id();
+    populate_bookmarks_ui(commands, theme.as_ref(), bookmarks, bookmarks_node);
+    spawn_search_field(commands, theme.as_ref()).insert(ChildOf(top_bar));
+    spawn_top_bar_button(commands, theme.as_ref(), ".*")
+        .insert((ShowHiddenFilesToggle, ChildOf(top_bar)))
+        .observe(toggle_hidden_files);
+    spawn_top_bar_button(commands, theme.as_ref(), "\u{2630}")
+        .insert((ViewModeToggle, ChildOf(top_bar)))
+        .observe(toggle_view_mode);
+    spawn_thumbnail_size_slider(commands, theme.as_ref(), thumbnail_size).insert(ChildOf(top_bar));
 
     commands.entity(top_bar)
 }
@@ -41,23 +134,287 @@ pub fn location_as_changed(location: Res<AssetBrowserLocation>) -> bool {
     location.is_changed()
 }
 
+/// Check if the [`AssetBrowserLocation`] has a parent directory to navigate to
+pub(crate) fn has_parent_directory(location: &AssetBrowserLocation) -> bool {
+    location.source_id.is_some() && location.path.parent().is_some()
+}
+
+/// Pop the last directory of the [`AssetBrowserLocation`] path
+fn navigate_to_parent_directory(
+    trigger: On<Pointer<Release>>,
+    mut commands: Commands,
+    mut location: ResMut<AssetBrowserLocation>,
+) {
+    if trigger.event().button != PointerButton::Primary || !has_parent_directory(&location) {
+        return;
+    }
+    location.path.pop();
+    commands.run_system_cached(io::task::fetch_directory_content);
+}
+
+/// Rescan the current [`AssetBrowserLocation`], files may have been changed outside of the editor
+fn refresh_directory_content(trigger: On<Pointer<Release>>, mut commands: Commands) {
+    if trigger.event().button != PointerButton::Primary {
+        return;
+    }
+    commands.queue(io::task::refresh_directory_content);
+}
+
+/// Go back to the previous location of the [`LocationHistory`]
+fn navigate_back(
+    trigger: On<Pointer<Release>>,
+    mut commands: Commands,
+    mut location: ResMut<AssetBrowserLocation>,
+    mut history: ResMut<LocationHistory>,
+) {
+    if trigger.event().button != PointerButton::Primary {
+        return;
+    }
+    if let Some(previous) = history.go_back() {
+        *location = previous;
+        commands.run_system_cached(io::task::fetch_directory_content);
+    }
+}
+
+/// Go forward to the location left by going back in the [`LocationHistory`]
+fn navigate_forward(
+    trigger: On<Pointer<Release>>,
+    mut commands: Commands,
+    mut location: ResMut<AssetBrowserLocation>,
+    mut history: ResMut<LocationHistory>,
+) {
+    if trigger.event().button != PointerButton::Primary {
+        return;
+    }
+    if let Some(next) = history.go_forward() {
+        *location = next;
+        commands.run_system_cached(io::task::fetch_directory_content);
+    }
+}
+
+/// Move to the location of the clicked [`BookmarkButton`]
+fn navigate_to_bookmark(
+    trigger: On<Pointer<Release>>,
+    mut commands: Commands,
+    button_query: Query<&BookmarkButton>,
+    bookmarks: Res<BrowserBookmarks>,
+    mut location: ResMut<AssetBrowserLocation>,
+) {
+    if trigger.event().button != PointerButton::Primary {
+        return;
+    }
+    let Some(bookmark) = button_query
+        .get(trigger.target())
+        .ok()
+        .and_then(|button| bookmarks.location(button.0))
+    else {
+        return;
+    };
+    *location = bookmark;
+    commands.run_system_cached(io::task::fetch_directory_content);
+}
+
+/// Remove the bookmark of a [`BookmarkButton`] from the [`BrowserBookmarks`]
+fn remove_bookmark(
+    button: In<Entity>,
+    button_query: Query<&BookmarkButton>,
+    mut bookmarks: ResMut<BrowserBookmarks>,
+) {
+    let Some(bookmark) = button_query
+        .get(*button)
+        .ok()
+        .and_then(|button| bookmarks.location(button.0))
+    else {
+        return;
+    };
+    bookmarks.remove(&bookmark);
+}
+
+/// Clear and regenerate the [`BookmarksNode`] buttons
+pub fn refresh_bookmarks(
+    mut commands: Commands,
+    theme: Res<Theme>,
+    bookmarks: Res<BrowserBookmarks>,
+    bookmarks_query: Query<(Entity, Option<&Children>), With<BookmarksNode>>,
+) {
+    for (bookmarks_node, buttons) in bookmarks_query.iter() {
+        if let Some(buttons) = buttons {
+            for button in buttons.iter() {
+                commands.entity(button).despawn();
+            }
+            commands.entity(bookmarks_node).remove::<Children>();
+        }
+        populate_bookmarks_ui(&mut commands, theme.as_ref(), &bookmarks, bookmarks_node);
+    }
+}
+
+/// Spawn a [`BookmarkButton`] for each of the [`BrowserBookmarks`] inside `bookmarks_node`
+fn populate_bookmarks_ui(
+    commands: &mut Commands,
+    theme: &Theme,
+    bookmarks: &BrowserBookmarks,
+    bookmarks_node: Entity,
+) {
+    for (index, (label, _, _)) in bookmarks.0.iter().enumerate() {
+        spawn_top_bar_button(commands, theme, label)
+            .insert((
+                BookmarkButton(index),
+                ContextMenu::new([ContextMenuOption::new(
+                    "Remove Bookmark",
+                    |mut commands, entity| {
+                        commands.run_system_cached_with(remove_bookmark, entity);
+                    },
+                )]),
+                ChildOf(bookmarks_node),
+            ))
+            .observe(navigate_to_bookmark);
+    }
+}
+
+/// Flip [`ShowHiddenFiles`] and fetch the directory content again, hidden entries being skipped while reading it
+fn toggle_hidden_files(
+    trigger: On<Pointer<Release>>,
+    mut commands: Commands,
+    mut show_hidden_files: ResMut<ShowHiddenFiles>,
+) {
+    if trigger.event().button != PointerButton::Primary {
+        return;
+    }
+    show_hidden_files.0 = !show_hidden_files.0;
+    commands.run_system_cached(io::task::fetch_directory_content);
+}
+
+/// Highlight the [`ShowHiddenFilesToggle`] while hidden entries are displayed
+pub fn refresh_hidden_files_toggle(
+    show_hidden_files: Res<ShowHiddenFiles>,
+    mut toggle_query: Query<&mut BackgroundColor, With<ShowHiddenFilesToggle>>,
+) {
+    for mut background_color in toggle_query.iter_mut() {
+        background_color.0 = if show_hidden_files.0 {
+            EditorColors::ACCENT_BLUE.with_alpha(0.3)
+        } else {
+            PATH_SEGMENT_BACKGROUND_COLOR
+        };
+    }
+}
+
+/// Switch the [`BrowserViewMode`] between the grid and the details view
+fn toggle_view_mode(trigger: On<Pointer<Release>>, mut view_mode: ResMut<BrowserViewMode>) {
+    if trigger.event().button != PointerButton::Primary {
+        return;
+    }
+    *view_mode = match *view_mode {
+        BrowserViewMode::Grid => BrowserViewMode::Details,
+        BrowserViewMode::Details => BrowserViewMode::Grid,
+    };
+}
+
+/// Highlight the [`ViewModeToggle`] while the details view is displayed
+pub fn refresh_view_mode_toggle(
+    view_mode: Res<BrowserViewMode>,
+    mut toggle_query: Query<&mut BackgroundColor, With<ViewModeToggle>>,
+) {
+    for mut background_color in toggle_query.iter_mut() {
+        background_color.0 = if *view_mode == BrowserViewMode::Details {
+            EditorColors::ACCENT_BLUE.with_alpha(0.3)
+        } else {
+            PATH_SEGMENT_BACKGROUND_COLOR
+        };
+    }
+}
+
 /// Clear and regenerate the location path UI
 pub fn refresh_ui(
     mut commands: Commands,
-    root: Query<(Entity, Option<&Children>), With<TopBarNode>>,
+    root: Query<(Entity, Option<&Children>), With<LocationPathNode>>,
     theme: Res<Theme>,
     location: Res<AssetBrowserLocation>,
 ) {
-    for (top_bar_entity, top_bar_childrens) in root.iter() {
+    for (location_path_entity, location_path_childrens) in root.iter() {
         // Clear location path UI
-        if let Some(childrens) = top_bar_childrens {
+        if let Some(childrens) = location_path_childrens {
             for child in childrens.iter() {
                 commands.entity(child).despawn();
             }
-            commands.entity(top_bar_entity).remove::<Children>();
+            commands.entity(location_path_entity).remove::<Children>();
         }
         // Regenerate location path UI
-        spawn_location_path_ui(&mut commands, &theme, &location).insert(ChildOf(top_bar_entity));
+        populate_location_path_ui(&mut commands, &theme, &location, location_path_entity);
+    }
+}
+
+/// Enable or disable the [`ParentDirectoryButton`] depending on the current [`AssetBrowserLocation`]
+pub fn refresh_parent_directory_button(
+    mut commands: Commands,
+    theme: Res<Theme>,
+    location: Res<AssetBrowserLocation>,
+    button_query: Query<(Entity, &Children), With<ParentDirectoryButton>>,
+    mut text_color_query: Query<&mut TextColor>,
+) {
+    let enabled = has_parent_directory(&location);
+    for (button, button_children) in button_query.iter() {
+        set_top_bar_button_enabled(
+            &mut commands,
+            &theme,
+            button,
+            button_children,
+            &mut text_color_query,
+            enabled,
+        );
+    }
+}
+
+/// Enable the [`BackButton`] and [`ForwardButton`] only when the [`LocationHistory`] has somewhere to go
+pub fn refresh_history_buttons(
+    mut commands: Commands,
+    theme: Res<Theme>,
+    history: Res<LocationHistory>,
+    back_query: Query<(Entity, &Children), With<BackButton>>,
+    forward_query: Query<(Entity, &Children), With<ForwardButton>>,
+    mut text_color_query: Query<&mut TextColor>,
+) {
+    let buttons = back_query
+        .iter()
+        .map(|button| (button, !history.back.is_empty()))
+        .chain(
+            forward_query
+                .iter()
+                .map(|button| (button, !history.forward.is_empty())),
+        );
+    for ((button, button_children), enabled) in buttons {
+        set_top_bar_button_enabled(
+            &mut commands,
+            &theme,
+            button,
+            button_children,
+            &mut text_color_query,
+            enabled,
+        );
+    }
+}
+
+/// Toggle [`InteractionDisabled`] on a top bar button, dimming its label while disabled
+fn set_top_bar_button_enabled(
+    commands: &mut Commands,
+    theme: &Theme,
+    button: Entity,
+    button_children: &Children,
+    text_color_query: &mut Query<&mut TextColor>,
+    enabled: bool,
+) {
+    if enabled {
+        commands.entity(button).remove::<InteractionDisabled>();
+    } else {
+        commands.entity(button).insert(InteractionDisabled);
+    }
+    for child in button_children.iter() {
+        if let Ok(mut text_color) = text_color_query.get_mut(child) {
+            text_color.0 = if enabled {
+                theme.text.text_color
+            } else {
+                theme.text.low_priority
+            };
+        }
     }
 }
 
@@ -68,13 +425,26 @@ pub fn spawn_location_path_ui<'a>(
     location: &Res<AssetBrowserLocation>,
 ) -> EntityCommands<'a> {
     let location_path = commands
-        .spawn(Node {
-            flex_direction: FlexDirection::Row,
-            align_items: AlignItems::Center,
-            ..default()
-        })
+        .spawn((
+            LocationPathNode,
+            Node {
+                flex_direction: FlexDirection::Row,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+        ))
         .id();
+    populate_location_path_ui(commands, theme, location, location_path);
+    commands.entity(location_path)
+}
 
+/// Spawn all the path segments of the current [`AssetBrowserLocation`] inside `location_path`
+fn populate_location_path_ui(
+    commands: &mut Commands,
+    theme: &Res<Theme>,
+    location: &Res<AssetBrowserLocation>,
+    location_path: Entity,
+) {
     spawn_path_segment_ui(
         commands,
         "Sources".to_string(),
@@ -108,7 +478,137 @@ pub fn spawn_location_path_ui<'a>(
             .insert(ChildOf(location_path));
         });
     }
-    commands.entity(location_path)
+}
+
+/// Spawn the text field editing the [`DirectoryFilter`]
+fn spawn_search_field<'a>(commands: &'a mut Commands, theme: &Theme) -> EntityCommands<'a> {
+    let mut search_field_ec = commands.spawn((
+        SearchField,
+        EditableTextLine::new(""),
+        Node {
+            width: Val::Px(150.0),
+            height: Val::Px(20.0),
+            margin: UiRect::left(Val::Auto),
+            padding: UiRect::horizontal(Val::Px(5.0)),
+            ..default()
+        },
+        TextFont {
+            font: theme.text.font.clone(),
+            font_size: 10.0,
+            ..default()
+        },
+        BackgroundColor(PATH_SEGMENT_BACKGROUND_COLOR),
+        theme.general.border_radius,
+    ));
+    search_field_ec.observe(
+        |trigger: On<TextChanged>, mut directory_filter: ResMut<DirectoryFilter>| {
+            directory_filter.0 = trigger.event().new_text.clone();
+        },
+    );
+    search_field_ec
+}
+
+/// Focus the [`SearchField`] on Ctrl+F (Cmd+F on macOS), and clear it on Escape to display the whole directory again
+///
+/// Only handled while the cursor is over the asset browser or its search field is focused,
+/// so the shortcuts don't steal the keys of other panes or of the rename field.
+pub(crate) fn search_shortcuts(
+    mut commands: Commands,
+    keys: Res<ButtonInput<KeyCode>>,
+    input_focus: Option<ResMut<InputFocus>>,
+    browser_query: Query<(Entity, &RelativeCursorPosition), With<AssetBrowserNode>>,
+    search_field_query: Query<(Entity, &EditableTextLine), With<SearchField>>,
+    query_child_of: Query<&ChildOf>,
+    mut directory_filter: ResMut<DirectoryFilter>,
+) {
+    #[cfg(target_os = "macos")]
+    let modifier_keys = [KeyCode::SuperLeft, KeyCode::SuperRight];
+    #[cfg(not(target_os = "macos"))]
+    let modifier_keys = [KeyCode::ControlLeft, KeyCode::ControlRight];
+
+    let focus_search = keys.any_pressed(modifier_keys) && keys.just_pressed(KeyCode::KeyF);
+    let clear_search = keys.just_pressed(KeyCode::Escape);
+    if !focus_search && !clear_search {
+        return;
+    }
+    let Some(mut input_focus) = input_focus else {
+        return;
+    };
+    let focused_search_field = input_focus
+        .0
+        .filter(|focused| search_field_query.contains(*focused));
+    // Another text field has the keyboard, leave the keys to it
+    if input_focus.0.is_some() && focused_search_field.is_none() {
+        return;
+    }
+    let hovered_search_field = browser_query
+        .iter()
+        .find(|(_, cursor)| cursor.cursor_over())
+        .and_then(|(browser, _)| {
+            search_field_query.iter().find(|(field, _)| {
+                query_child_of
+                    .iter_ancestors(*field)
+                    .any(|ancestor| ancestor == browser)
+            })
+        })
+        .map(|(field, _)| field);
+    let Some(search_field) = focused_search_field.or(hovered_search_field) else {
+        return;
+    };
+
+    if focus_search {
+        input_focus.0 = Some(search_field);
+        if let Ok((_, text_line)) = search_field_query.get(search_field) {
+            commands.trigger_targets(
+                SetCursorPosition(CharPosition(text_line.text.chars().count())),
+                search_field,
+            );
+        }
+    } else {
+        // The filter is shared by all the asset browsers, clear every search field displaying it
+        for (field, text_line) in search_field_query.iter() {
+            if !text_line.text.is_empty() {
+                commands.trigger_targets(SetText(String::new()), field);
+            }
+        }
+        if !directory_filter.0.is_empty() {
+            directory_filter.0.clear();
+        }
+        if focused_search_field.is_some() {
+            input_focus.0 = None;
+        }
+    }
+}
+
+/// Spawn a top bar button displaying a short label
+pub(super) fn spawn_top_bar_button<'a>(
+    commands: &'a mut Commands,
+    theme: &Theme,
+    label: &str,
+) -> EntityCommands<'a> {
+    let mut button_ec = commands.spawn((
+        Button,
+        Node {
+            padding: UiRect::axes(Val::Px(6.0), Val::Px(1.0)),
+            margin: UiRect::horizontal(Val::Px(2.0)),
+            ..default()
+        },
+        BackgroundColor(PATH_SEGMENT_BACKGROUND_COLOR),
+        theme.general.border_radius,
+        EntityCursor::System(SystemCursorIcon::Pointer),
+    ));
+    button_ec.with_children(|parent| {
+        parent.spawn((
+            Text::new(label),
+            TextFont {
+                font: theme.text.font.clone(),
+                font_size: 10.0,
+                ..default()
+            },
+            TextColor(theme.text.text_color),
+        ));
+    });
+    button_ec
 }
 
 /// Spawn a path segment UI element
@@ -154,8 +654,7 @@ fn spawn_path_segment_ui<'a>(
                 let (parent, segment_type) = query_segment_info.get(segment).unwrap();
                 match segment_type {
                     LocationSegmentType::Root => {
-                        location.source_id = None;
-                        location.path.clear();
+                        *location = AssetBrowserLocation::sources_root();
                     }
                     LocationSegmentType::Source => {
                         location.path.clear();
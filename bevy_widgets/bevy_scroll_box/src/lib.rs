@@ -31,6 +31,21 @@ impl ScrollBox {
     pub fn scroll_to_top(&mut self) {
         self.position = ScrollPosition::default();
     }
+
+    /// How far the content is scrolled from its top left corner
+    pub fn scroll_offset(&self) -> Vec2 {
+        Vec2::new(-self.position.x, -self.position.y)
+    }
+
+    /// Scroll vertically by the minimum amount needed to fully show the `top..bottom` range of the content,
+    /// `view_height` being the visible height of the scroll box
+    pub fn scroll_into_view(&mut self, top: f32, bottom: f32, view_height: f32) {
+        if top + self.position.y < 0.0 {
+            self.position.y = -top;
+        } else if bottom + self.position.y > view_height {
+            self.position.y = (view_height - bottom).min(0.0);
+        }
+    }
 }
 
 /// Represents the content within a [`ScrollBox`].